@@ -6,14 +6,21 @@ use avian3d::prelude::RigidBody;
 use bevy::asset::Assets;
 use bevy::DefaultPlugins;
 use bevy::pbr::{PointLight, StandardMaterial};
-use bevy::prelude::{default, Added, App, Color, Commands, Cylinder, Entity, First, Mesh, Mesh3d, MeshMaterial3d, Query, ResMut, Startup, Transform, With};
+use bevy::prelude::{default, Added, App, Color, Commands, Cuboid, Cylinder, Entity, First, Mesh, Mesh3d, MeshMaterial3d, Query, Res, ResMut, Sphere, Startup, Transform, With, Without};
 use bevy_inspector_egui::quick::WorldInspectorPlugin;
 use lightyear::prelude::Replicated;
-use shared::GameMask;
+use lightyear::prelude::client::Predicted;
+use shared::{GameMask, NetworkSide};
+use shared::plugins::platforms::{MovingPlatform, MovingPlatformClientBundle};
+use shared::plugins::projectile::{Projectile, ProjectileSettings};
 use shared::protocol::FloorMarker;
 use crate::plugins::animations::AnimationPlugin;
+use crate::plugins::chat::ChatPlugin;
 use crate::plugins::combatant::CombatantPlugin;
 use crate::plugins::connection::ClientPlugin;
+use crate::plugins::diagnostics::ClientDiagnosticsPlugin;
+use crate::plugins::latency::AdaptiveLatencyPlugin;
+use crate::plugins::misprediction::MispredictionPlugin;
 
 fn default_stuff(
     mut commands: Commands,
@@ -40,14 +47,45 @@ fn floor_load(
             Mesh3d(meshes.add(Cylinder::new(50.0, 0.1))),
             MeshMaterial3d(materials.add(Color::WHITE)),
             GameMask::Floor,
+            GameMask::floor_layers(),
+        ));
+    }
+}
+
+fn platform_load(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+    platform_query: Query<(Entity, &MovingPlatform),(With<Predicted>, Without<NetworkSide>)>
+){
+    for (entity, moving_platform) in &platform_query {
+        commands.entity(entity).insert((
+            MovingPlatformClientBundle::new(moving_platform),
+            Mesh3d(meshes.add(Cuboid::from_size(moving_platform.size))),
+            MeshMaterial3d(materials.add(Color::srgb(0.6, 0.6, 0.7))),
+        ));
+    }
+}
+
+fn projectile_load(
+    mut commands: Commands,
+    mut meshes: ResMut<Assets<Mesh>>,
+    mut materials: ResMut<Assets<StandardMaterial>>,
+    projectile_query: Query<Entity,(With<Projectile>, With<NetworkSide>, Without<Mesh3d>)>,
+    settings: Res<ProjectileSettings>,
+){
+    for entity in &projectile_query {
+        commands.entity(entity).insert((
+            Mesh3d(meshes.add(Sphere::new(settings.radius))),
+            MeshMaterial3d(materials.add(Color::srgb(1.0, 0.5, 0.1))),
         ));
     }
 }
 
 fn main() {
     App::new()
-        .add_plugins((DefaultPlugins,WorldInspectorPlugin::new(),ClientPlugin,AnimationPlugin,CombatantPlugin))
+        .add_plugins((DefaultPlugins,WorldInspectorPlugin::new(),ClientPlugin,AnimationPlugin,CombatantPlugin,ClientDiagnosticsPlugin,MispredictionPlugin,AdaptiveLatencyPlugin,ChatPlugin))
         .add_systems(Startup,default_stuff)
-        .add_systems(First,floor_load)
+        .add_systems(First,(floor_load,platform_load,projectile_load))
         .run();
 }
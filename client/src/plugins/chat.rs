@@ -0,0 +1,43 @@
+use std::collections::VecDeque;
+use bevy::app::{App, Plugin, Startup, Update};
+use bevy::prelude::{IntoSystemConfigs, Resource};
+use crate::systems::chat::{handle_chat_input, receive_chat_messages, spawn_chat_box, update_chat_box};
+
+pub struct ChatPlugin;
+
+#[derive(Resource)]
+pub struct ChatBox{
+    pub open: bool,
+    pub input: String,
+    pub history: VecDeque<String>,
+    pub max_history: usize
+}
+
+impl Default for ChatBox {
+    fn default() -> Self {
+        Self {
+            open: false,
+            input: String::new(),
+            history: VecDeque::new(),
+            max_history: 8
+        }
+    }
+}
+
+impl ChatBox {
+    pub fn push(&mut self, line: String){
+        self.history.push_back(line);
+
+        while self.history.len() > self.max_history {
+            self.history.pop_front();
+        }
+    }
+}
+
+impl Plugin for ChatPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_resource::<ChatBox>();
+        app.add_systems(Startup,spawn_chat_box);
+        app.add_systems(Update,(handle_chat_input,receive_chat_messages,update_chat_box).chain());
+    }
+}
@@ -1,29 +1,34 @@
 use bevy::app::App;
-use bevy::prelude::{FixedUpdate, IntoSystemConfigs, Plugin, PostUpdate, Query, Res, TransformSystem, With};
+use bevy::prelude::{EventReader, FixedUpdate, IntoSystemConfigs, Plugin, PostUpdate, Query, Res, TransformSystem, Update, With};
 use leafwing_input_manager::prelude::ActionState;
 use lightyear::inputs::leafwing::input_buffer::InputBuffer;
 use lightyear::prelude::client::Rollback;
 use lightyear::prelude::TickManager;
 use shared::InteractNetworkAble;
-use shared::plugins::combatant::PlayerCombatant;
+use shared::plugins::knockback::{is_stunned, Knockback};
+use shared::plugins::combatant::{CharacterControllerSettings, DashStarted, PlayerCombatant, Stamina};
 use shared::plugins::statesmachine::{CurrentStates, StatesApplied};
-use shared::protocol::CharacterAction;
-use shared::systems::characteractions::move_action;
+use shared::protocol::{CharacterAction, ClientMessages, CosmeticChannel, CosmeticEvent, CosmeticEventMessage};
+use shared::systems::characteractions::{block_action, crouch_action, move_action, sprint_action};
 use shared::systems::charactercontroller::check_is_grounded;
 use crate::systems::camera::{create_combatant_camera,update_combatant_camera_transform};
-use crate::systems::states::{check_idle_state, check_walking_state};
+use crate::systems::combat::{receive_cosmetic_events, receive_hit_results, receive_kill_notices, CosmeticReaction, HitReaction};
+use crate::systems::states::{check_idle_state, check_walking_state, update_combatant_mesh_height};
 
 pub struct CombatantPlugin;
 
 impl Plugin for CombatantPlugin{
     fn build(&self, app: &mut App) {
+        app.add_event::<HitReaction>();
+        app.add_event::<CosmeticReaction>();
+        app.add_systems(Update,(receive_hit_results,receive_kill_notices,receive_cosmetic_events,update_combatant_mesh_height,send_dash_cosmetics));
         app.add_systems(FixedUpdate,(check_idle_state,check_walking_state).before(check_is_grounded));
         app.add_systems(PostUpdate,(create_combatant_camera,update_combatant_camera_transform,handle_combatant_actions).chain().before(TransformSystem::TransformPropagate));
     }
 }
 
 pub fn handle_combatant_actions(
-    mut query: Query<(&ActionState<CharacterAction>, &InputBuffer<CharacterAction>, &mut CurrentStates),(With<InteractNetworkAble>, With<PlayerCombatant>, With<StatesApplied>)>,
+    mut query: Query<(&ActionState<CharacterAction>, &InputBuffer<CharacterAction>, &CharacterControllerSettings, &Stamina, Option<&Knockback>, &mut CurrentStates),(With<InteractNetworkAble>, With<PlayerCombatant>, With<StatesApplied>)>,
     tick_manager: Res<TickManager>,
     rollback: Option<Res<Rollback>>,
 ){
@@ -32,7 +37,11 @@ pub fn handle_combatant_actions(
         .map(|rb| tick_manager.tick_or_rollback_tick(rb))
         .unwrap_or(tick_manager.tick());
 
-    for (action_state, input_buffer, mut current_states) in query.iter_mut() {
+    for (action_state, input_buffer, settings, stamina, knockback, mut current_states) in query.iter_mut() {
+        if is_stunned(knockback, tick) {
+            continue;
+        }
+
         let action_state_correctly = if input_buffer.get(tick).is_some() {action_state} else {
             if let Some((_, prev_action_state)) = input_buffer.get_last_with_tick() {prev_action_state} else {action_state}
         };
@@ -41,6 +50,27 @@ pub fn handle_combatant_actions(
             .axis_pair(&CharacterAction::Move)
             .clamp_length_max(1.0);
 
+        block_action(action_state_correctly.pressed(&CharacterAction::Block), tick, &mut current_states);
         move_action(move_dir, &mut current_states);
+        crouch_action(action_state_correctly.pressed(&CharacterAction::Crouch), action_state_correctly.just_pressed(&CharacterAction::Crouch), settings.crouch_toggle, &mut current_states);
+        sprint_action(action_state_correctly.pressed(&CharacterAction::Sprint), stamina, &mut current_states);
+    }
+}
+
+pub fn send_dash_cosmetics(
+    mut dash_started_events: EventReader<DashStarted>,
+    player_query: Query<(), With<PlayerCombatant>>,
+    mut messages: ClientMessages,
+){
+    for event in dash_started_events.read() {
+        if !player_query.contains(event.entity) {
+            continue;
+        }
+
+        messages.send_to_server::<CosmeticChannel, CosmeticEventMessage>(&mut CosmeticEventMessage{
+            source: None,
+            event: CosmeticEvent::Dash,
+            position: event.position
+        });
     }
 }
\ No newline at end of file
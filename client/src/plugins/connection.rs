@@ -1,10 +1,11 @@
 use bevy::app::{App, Plugin};
-use bevy::prelude::{Commands, Startup};
+use bevy::log::info;
+use bevy::prelude::{Commands, Local, Res, ResMut, Startup, Time, Update};
 use bevy::utils::default;
 use lightyear::connection::client::{IoConfig, NetConfig};
 use lightyear::prelude::client::{Authentication, ClientCommandsExt, ClientConfig, ClientPlugins, ClientTransport, PredictionConfig};
 use shared::NetworkSide;
-use shared::plugins::shared::{settings, shared_configs, Settings, SharedPlugin, CLIENT_ADDR, PRIVATE_KEY, PROTOCOL_ID, SERVER_ADDR};
+use shared::plugins::shared::{link_conditioner_settled, protocol_id, settings, shared_configs, LinkConditionerSettings, Settings, SharedPlugin, CLIENT_ADDR, PRIVATE_KEY, SERVER_ADDR};
 
 pub struct ClientPlugin;
 
@@ -14,15 +15,20 @@ impl Plugin for ClientPlugin {
 
         app.add_plugins((setup_client_plugins(&settings), SharedPlugin{
             predict_all: settings.predict_all,
+            compact_combatant_state: settings.compact_combatant_state,
             network_side: NetworkSide::Client
         }));
+        app.register_type::<LinkConditionerSettings>();
+        app.insert_resource(settings.link_conditioner);
         app.add_systems(Startup,connect_to_server);
+        app.add_systems(Update,apply_link_conditioner);
     }
 }
 
-fn setup_net_config() -> NetConfig{
+fn setup_net_config(settings: &Settings) -> NetConfig{
     let io_config = IoConfig{
         transport: ClientTransport::UdpSocket(CLIENT_ADDR),
+        conditioner: settings.link_conditioner.config(),
         ..default()
     };
 
@@ -30,7 +36,7 @@ fn setup_net_config() -> NetConfig{
         server_addr: SERVER_ADDR,
         client_id: 1,
         private_key: PRIVATE_KEY,
-        protocol_id: PROTOCOL_ID,
+        protocol_id: protocol_id(settings.compact_combatant_state),
     };
 
     NetConfig::Netcode {
@@ -48,7 +54,7 @@ fn setup_client_plugins(settings: &Settings) -> ClientPlugins {
 
     ClientPlugins::new(ClientConfig {
         shared: shared_configs(),
-        net: setup_net_config(),
+        net: setup_net_config(settings),
         prediction: prediction_config,
         ..default()
     })
@@ -56,4 +62,20 @@ fn setup_client_plugins(settings: &Settings) -> ClientPlugins {
 
 fn connect_to_server(mut commands: Commands) {
     commands.connect_client();
-}
\ No newline at end of file
+}
+fn apply_link_conditioner(
+    mut pending_since: Local<Option<f32>>,
+    link_conditioner: Res<LinkConditionerSettings>,
+    mut client_config: ResMut<ClientConfig>,
+    time: Res<Time>,
+){
+    if !link_conditioner_settled(&link_conditioner, &mut pending_since, &time) {
+        return;
+    }
+
+    if let NetConfig::Netcode{io, ..} = &mut client_config.net {
+        io.conditioner = link_conditioner.config();
+    }
+
+    info!("Link conditioner set to {:?}, applied on the next connection", *link_conditioner);
+}
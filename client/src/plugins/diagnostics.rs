@@ -0,0 +1,24 @@
+use bevy::app::{App, FixedUpdate, Plugin, Startup, Update};
+use bevy::prelude::{IntoSystemConfigs, Resource};
+use shared::plugins::diagnostics::NetworkDiagnosticsPlugin;
+use crate::systems::diagnostics::{count_rollbacks, export_client_diagnostics, sample_client_diagnostics, spawn_client_diagnostics_hud, toggle_client_diagnostics};
+
+pub struct ClientDiagnosticsPlugin;
+
+#[derive(Resource, Default)]
+pub struct ClientDiagnosticsCounters{
+    pub rollbacks: u32,
+    pub rollback_depth_total: u32,
+    pub last_rollback_frame: Option<u32>,
+    pub timer: f32
+}
+
+impl Plugin for ClientDiagnosticsPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_plugins(NetworkDiagnosticsPlugin);
+        app.init_resource::<ClientDiagnosticsCounters>();
+        app.add_systems(Startup,spawn_client_diagnostics_hud);
+        app.add_systems(FixedUpdate,count_rollbacks);
+        app.add_systems(Update,(sample_client_diagnostics,toggle_client_diagnostics,export_client_diagnostics).chain());
+    }
+}
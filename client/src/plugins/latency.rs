@@ -0,0 +1,55 @@
+use bevy::app::{App, Plugin, Update};
+use bevy::prelude::{Reflect, ReflectResource, Resource};
+use crate::systems::latency::adapt_input_delay;
+
+pub struct AdaptiveLatencyPlugin;
+
+#[derive(Resource, Clone, Debug, Reflect)]
+#[reflect(Resource)]
+pub struct AdaptiveLatencySettings{
+    pub enabled: bool,
+    pub update_interval: f32,
+    pub min_input_delay_ticks: u16,
+    pub max_input_delay_ticks: u16,
+    pub delayed_rtt_share: f32,
+    pub jitter_margin: f32,
+    pub min_correction_ticks_factor: f32,
+    pub max_correction_ticks_factor: f32,
+    pub correction_jitter_weight: f32,
+    pub correction_step: f32
+}
+
+#[derive(Resource, Default, Debug, Reflect)]
+#[reflect(Resource)]
+pub struct AdaptiveLatencyState{
+    pub timer: f32,
+    pub target_input_delay_ticks: u16,
+    pub target_correction_ticks_factor: f32
+}
+
+impl Default for AdaptiveLatencySettings {
+    fn default() -> Self {
+        Self {
+            enabled: !std::env::args().any(|argument| argument == "--fixed-input-delay"),
+            update_interval: 1.0,
+            min_input_delay_ticks: 0,
+            max_input_delay_ticks: 6,
+            delayed_rtt_share: 0.5,
+            jitter_margin: 1.0,
+            min_correction_ticks_factor: 1.0,
+            max_correction_ticks_factor: 6.0,
+            correction_jitter_weight: 0.5,
+            correction_step: 0.25
+        }
+    }
+}
+
+impl Plugin for AdaptiveLatencyPlugin {
+    fn build(&self, app: &mut App) {
+        app.register_type::<AdaptiveLatencySettings>();
+        app.register_type::<AdaptiveLatencyState>();
+        app.init_resource::<AdaptiveLatencySettings>();
+        app.init_resource::<AdaptiveLatencyState>();
+        app.add_systems(Update,adapt_input_delay);
+    }
+}
@@ -0,0 +1,56 @@
+use std::fmt::Debug;
+use avian3d::prelude::{AngularVelocity, ComputedMass, ExternalForce, ExternalImpulse, LinearVelocity, PhysicsSet, Position, Rotation};
+use bevy::app::{App, FixedPostUpdate, Plugin, Update};
+use bevy::prelude::{Component, IntoSystemConfigs, Reflect, ReflectResource, Res, Resource};
+use bevy::utils::hashbrown::HashMap;
+use shared::plugins::statesmachine::CurrentStates;
+use crate::systems::misprediction::{compare_confirmed_values, record_predicted_snapshots};
+
+pub struct MispredictionPlugin;
+
+#[derive(Resource, Clone, Debug, Reflect)]
+#[reflect(Resource)]
+pub struct MispredictionDiagnostics{
+    pub enabled: bool,
+    pub log_values: bool
+}
+
+#[derive(Resource, Default, Debug, Reflect)]
+#[reflect(Resource)]
+pub struct MispredictionCounts(pub HashMap<String,u32>);
+
+impl Default for MispredictionDiagnostics {
+    fn default() -> Self {
+        Self {
+            enabled: std::env::args().any(|argument| argument == "--mispredictions"),
+            log_values: true
+        }
+    }
+}
+
+impl Plugin for MispredictionPlugin {
+    fn build(&self, app: &mut App) {
+        app.register_type::<MispredictionDiagnostics>();
+        app.register_type::<MispredictionCounts>();
+        app.init_resource::<MispredictionDiagnostics>();
+        app.init_resource::<MispredictionCounts>();
+
+        track_misprediction::<Position>(app);
+        track_misprediction::<Rotation>(app);
+        track_misprediction::<LinearVelocity>(app);
+        track_misprediction::<AngularVelocity>(app);
+        track_misprediction::<ExternalForce>(app);
+        track_misprediction::<ExternalImpulse>(app);
+        track_misprediction::<ComputedMass>(app);
+        track_misprediction::<CurrentStates>(app);
+    }
+}
+
+fn track_misprediction<C: Component + Clone + PartialEq + Debug>(app: &mut App){
+    app.add_systems(FixedPostUpdate,record_predicted_snapshots::<C>.after(PhysicsSet::Sync).run_if(misprediction_diagnostics_enabled));
+    app.add_systems(Update,compare_confirmed_values::<C>.run_if(misprediction_diagnostics_enabled));
+}
+
+fn misprediction_diagnostics_enabled(diagnostics: Res<MispredictionDiagnostics>) -> bool{
+    diagnostics.enabled
+}
@@ -1,3 +1,7 @@
 pub mod connection;
 pub mod animations;
-pub mod combatant;
\ No newline at end of file
+pub mod combatant;
+pub mod diagnostics;
+pub mod misprediction;
+pub mod latency;
+pub mod chat;
\ No newline at end of file
@@ -1,6 +1,8 @@
+use avian3d::prelude::Collider;
 use bevy::prelude::{Camera3d, Commands, Component, Entity, PerspectiveProjection, Projection, Query, Transform, Vec3, With, Without};
 use bevy::utils::default;
-use shared::plugins::combatant::PlayerCombatant;
+use shared::plugins::combatant::{CharacterController, CharacterControllerSettings, PlayerCombatant};
+use shared::systems::charactercontroller::{collider_half_extents, crouch_ratio};
 
 #[derive(Component)]
 pub struct CameraAttached;
@@ -37,15 +39,19 @@ pub fn create_combatant_camera(
 }
 
 pub fn update_combatant_camera_transform(
-    character_query: Query<&Transform, With<CameraAttached>>,
+    character_query: Query<(&Transform, &Collider, &CharacterController, &CharacterControllerSettings), With<CameraAttached>>,
     mut camera_query: Query<(&mut Transform, &CombatantCamera), (With<CombatantCamera>, Without<CameraAttached>)>
 ){
-    for character_transform in character_query.iter() {
+    for (character_transform, collider, character_controller, settings) in character_query.iter() {
+        let current_height = collider_half_extents(collider).y * 2.0;
+        let crouch_ratio = crouch_ratio(settings);
+        let crouch_offset = if character_controller.crouched {current_height - current_height / crouch_ratio} else {0.0};
+
         for (mut camera_transform,combatant_camera) in camera_query.iter_mut() {
             let current_translation = character_transform.translation;
             let forward_vector = character_transform.forward().as_vec3();
             let left_vector = character_transform.left().as_vec3();
-            let new_camera_translation = (current_translation + (forward_vector * combatant_camera.distance) + (left_vector * combatant_camera.offset_side)) + Vec3::new(0.0,combatant_camera.height,0.0);
+            let new_camera_translation = (current_translation + (forward_vector * combatant_camera.distance) + (left_vector * combatant_camera.offset_side)) + Vec3::new(0.0,combatant_camera.height + crouch_offset,0.0);
 
             camera_transform.translation = new_camera_translation;
             camera_transform.look_at(new_camera_translation - forward_vector,Vec3::Y);
@@ -0,0 +1,173 @@
+use bevy::input::ButtonState;
+use bevy::input::keyboard::{Key, KeyboardInput};
+use bevy::prelude::{BuildChildren, Commands, Component, DetectChanges, EventReader, FlexDirection, Node, PositionType, Query, Res, ResMut, Text, Val, With, Without};
+use bevy::utils::default;
+use leafwing_input_manager::prelude::ActionState;
+use lightyear::prelude::ClientId;
+use lightyear::prelude::client::ClientReceiveMessage;
+use shared::plugins::combatant::PlayerCombatant;
+use shared::protocol::{CharacterAction, ChatChannel, ChatMessage, ChatMuteConfirmedMessage, ChatMuteMessage, ChatRejectedMessage, ChatRequestMessage, ChatTarget, ClientMessages, MAX_CHAT_LENGTH};
+use crate::plugins::chat::ChatBox;
+
+#[derive(Component)]
+pub struct ChatHistoryText;
+
+#[derive(Component)]
+pub struct ChatInputText;
+
+enum ChatCommand{
+    Send(ChatTarget, String),
+    Mute(ClientId, bool),
+    Invalid(String)
+}
+
+pub fn spawn_chat_box(
+    mut commands: Commands,
+){
+    commands.spawn(Node {
+        position_type: PositionType::Absolute,
+        bottom: Val::Px(8.0),
+        left: Val::Px(8.0),
+        flex_direction: FlexDirection::Column,
+        ..default()
+    }).with_children(|parent| {
+        parent.spawn((ChatHistoryText, Text::new("")));
+        parent.spawn((ChatInputText, Text::new("")));
+    });
+}
+
+pub fn handle_chat_input(
+    mut keyboard_events: EventReader<KeyboardInput>,
+    mut chat_box: ResMut<ChatBox>,
+    mut messages: ClientMessages,
+    mut action_query: Query<&mut ActionState<CharacterAction>, With<PlayerCombatant>>,
+){
+    for event in keyboard_events.read() {
+        if event.state != ButtonState::Pressed {
+            continue;
+        }
+
+        if !chat_box.open {
+            if event.logical_key == Key::Enter {
+                chat_box.open = true;
+                action_query.iter_mut().for_each(|mut action_state| action_state.disable_all());
+            }
+
+            continue;
+        }
+
+        match &event.logical_key {
+            Key::Enter => {
+                let input = std::mem::take(&mut chat_box.input);
+
+                match parse_chat_command(&input) {
+                    ChatCommand::Send(target, text) => messages.send_to_server::<ChatChannel, ChatRequestMessage>(&mut ChatRequestMessage{target, text}),
+                    ChatCommand::Mute(client_id, muted) => messages.send_to_server::<ChatChannel, ChatMuteMessage>(&mut ChatMuteMessage{client_id, muted}),
+                    ChatCommand::Invalid(reason) => chat_box.push(format!("[System] {reason}"))
+                }
+
+                close_chat_box(&mut chat_box, &mut action_query);
+            },
+            Key::Escape => {
+                chat_box.input.clear();
+                close_chat_box(&mut chat_box, &mut action_query);
+            },
+            Key::Backspace => {
+                chat_box.input.pop();
+            },
+            Key::Space => push_input(&mut chat_box, " "),
+            Key::Character(characters) => push_input(&mut chat_box, characters),
+            _ => {}
+        }
+    }
+}
+
+pub fn receive_chat_messages(
+    mut chat_messages: EventReader<ClientReceiveMessage<ChatMessage>>,
+    mut chat_rejections: EventReader<ClientReceiveMessage<ChatRejectedMessage>>,
+    mut chat_mutes: EventReader<ClientReceiveMessage<ChatMuteConfirmedMessage>>,
+    mut chat_box: ResMut<ChatBox>,
+){
+    for event in chat_messages.read() {
+        let message = event.message();
+        let channel = match message.target {
+            ChatTarget::All => "All".to_string(),
+            ChatTarget::Team => "Team".to_string(),
+            ChatTarget::Whisper(recipient) => format!("Whisper {recipient:?}"),
+            ChatTarget::Proximity => "Nearby".to_string()
+        };
+
+        chat_box.push(format!("[{channel}] {:?}: {}", message.sender, message.text));
+    }
+
+    for event in chat_rejections.read() {
+        chat_box.push(format!("[System] Message rejected: {:?}", event.message().reason));
+    }
+
+    for event in chat_mutes.read() {
+        let message = event.message();
+
+        chat_box.push(format!("[System] {} {:?}", if message.muted {"Muted"} else {"Unmuted"}, message.client_id));
+    }
+}
+
+pub fn update_chat_box(
+    chat_box: Res<ChatBox>,
+    mut history_query: Query<&mut Text, (With<ChatHistoryText>, Without<ChatInputText>)>,
+    mut input_query: Query<&mut Text, (With<ChatInputText>, Without<ChatHistoryText>)>,
+){
+    if !chat_box.is_changed() {
+        return;
+    }
+
+    for mut text in history_query.iter_mut() {
+        text.0 = chat_box.history.iter().cloned().collect::<Vec<String>>().join("\n");
+    }
+
+    for mut text in input_query.iter_mut() {
+        text.0 = if chat_box.open {format!("> {}", chat_box.input)} else {String::new()};
+    }
+}
+
+fn push_input(chat_box: &mut ChatBox, characters: &str){
+    if chat_box.input.chars().count() + characters.chars().count() <= MAX_CHAT_LENGTH {
+        chat_box.input.push_str(characters);
+    }
+}
+
+fn close_chat_box(chat_box: &mut ChatBox, action_query: &mut Query<&mut ActionState<CharacterAction>, With<PlayerCombatant>>){
+    chat_box.open = false;
+    action_query.iter_mut().for_each(|mut action_state| action_state.enable_all());
+}
+
+fn parse_client_id(value: &str) -> Option<ClientId>{
+    value.parse::<u64>().ok().map(ClientId::Netcode)
+}
+
+fn parse_chat_command(input: &str) -> ChatCommand{
+    let input = input.trim();
+    let Some(command) = input.strip_prefix('/') else {
+        return ChatCommand::Send(ChatTarget::All, input.to_string());
+    };
+
+    let (name, rest) = command.split_once(' ').unwrap_or((command, ""));
+
+    match name {
+        "a" | "all" => ChatCommand::Send(ChatTarget::All, rest.to_string()),
+        "t" | "team" => ChatCommand::Send(ChatTarget::Team, rest.to_string()),
+        "p" | "near" => ChatCommand::Send(ChatTarget::Proximity, rest.to_string()),
+        "w" | "whisper" => {
+            let (recipient, text) = rest.split_once(' ').unwrap_or((rest, ""));
+
+            match parse_client_id(recipient) {
+                Some(recipient) => ChatCommand::Send(ChatTarget::Whisper(recipient), text.to_string()),
+                None => ChatCommand::Invalid("Usage: /w <client id> <message>".to_string())
+            }
+        },
+        "mute" | "unmute" => match parse_client_id(rest.trim()) {
+            Some(client_id) => ChatCommand::Mute(client_id, name == "mute"),
+            None => ChatCommand::Invalid(format!("Usage: /{name} <client id>"))
+        },
+        _ => ChatCommand::Invalid(format!("Unknown command /{name}"))
+    }
+}
@@ -0,0 +1,77 @@
+use bevy::log::info;
+use bevy::math::Vec3;
+use bevy::prelude::{Entity, Event, EventReader, EventWriter, Query};
+use lightyear::prelude::client::{ClientReceiveMessage, Confirmed};
+use shared::plugins::health::HitResult;
+use shared::protocol::{CosmeticEvent, CosmeticEventMessage, HitResultMessage, KillNoticeMessage};
+
+#[derive(Event)]
+#[allow(dead_code)]
+pub struct HitReaction{
+    pub target: Entity,
+    pub attacker: Option<Entity>,
+    pub result: HitResult,
+    pub amount: f32
+}
+
+#[derive(Event)]
+#[allow(dead_code)]
+pub struct CosmeticReaction{
+    pub source: Option<Entity>,
+    pub event: CosmeticEvent,
+    pub position: Vec3
+}
+
+fn local_entity(entity: Entity, confirmed_query: &Query<&Confirmed>) -> Entity{
+    confirmed_query.get(entity).ok()
+        .and_then(|confirmed| confirmed.predicted.or(confirmed.interpolated))
+        .unwrap_or(entity)
+}
+
+pub fn receive_hit_results(
+    mut hit_result_messages: EventReader<ClientReceiveMessage<HitResultMessage>>,
+    mut hit_reaction_events: EventWriter<HitReaction>,
+    confirmed_query: Query<&Confirmed>,
+){
+    for event in hit_result_messages.read() {
+        let message = event.message();
+
+        hit_reaction_events.send(HitReaction{
+            target: local_entity(message.target, &confirmed_query),
+            attacker: message.attacker.map(|attacker| local_entity(attacker, &confirmed_query)),
+            result: message.result,
+            amount: message.amount
+        });
+    }
+}
+
+pub fn receive_kill_notices(
+    mut kill_notice_messages: EventReader<ClientReceiveMessage<KillNoticeMessage>>,
+    confirmed_query: Query<&Confirmed>,
+){
+    for event in kill_notice_messages.read() {
+        let message = event.message();
+        let victim = local_entity(message.victim, &confirmed_query);
+
+        match message.killer.map(|killer| local_entity(killer, &confirmed_query)) {
+            Some(killer) => info!("{killer:?} killed {victim:?}"),
+            None => info!("{victim:?} died")
+        }
+    }
+}
+
+pub fn receive_cosmetic_events(
+    mut cosmetic_messages: EventReader<ClientReceiveMessage<CosmeticEventMessage>>,
+    mut cosmetic_reaction_events: EventWriter<CosmeticReaction>,
+    confirmed_query: Query<&Confirmed>,
+){
+    for event in cosmetic_messages.read() {
+        let message = event.message();
+
+        cosmetic_reaction_events.send(CosmeticReaction{
+            source: message.source.map(|source| local_entity(source, &confirmed_query)),
+            event: message.event,
+            position: message.position
+        });
+    }
+}
@@ -0,0 +1,132 @@
+use std::path::Path;
+use avian3d::prelude::{Position, Rotation};
+use bevy::core::FrameCount;
+use bevy::diagnostic::DiagnosticsStore;
+use bevy::input::ButtonInput;
+use bevy::prelude::{Commands, Component, KeyCode, Node, PositionType, Query, Res, ResMut, Text, Time, Val, Visibility, With};
+use bevy::utils::default;
+use lightyear::prelude::client::{ClientConfig, ConnectionManager, Correction, Rollback};
+use lightyear::prelude::{IoDiagnosticsPlugin, TickManager};
+use shared::plugins::diagnostics::{ChannelStats, NetworkDiagnosticsLog, NetworkDiagnosticsSettings, NetworkSample};
+use crate::plugins::diagnostics::ClientDiagnosticsCounters;
+
+#[derive(Component)]
+pub struct NetworkDiagnosticsText;
+
+pub fn spawn_client_diagnostics_hud(
+    mut commands: Commands,
+){
+    commands.spawn((
+        NetworkDiagnosticsText,
+        Text::new(""),
+        Node {
+            position_type: PositionType::Absolute,
+            top: Val::Px(8.0),
+            left: Val::Px(8.0),
+            ..default()
+        },
+        Visibility::Hidden,
+    ));
+}
+
+pub fn count_rollbacks(
+    rollback: Option<Res<Rollback>>,
+    tick_manager: Res<TickManager>,
+    frame_count: Res<FrameCount>,
+    mut counters: ResMut<ClientDiagnosticsCounters>,
+){
+    let Some(rollback_tick) = rollback.as_ref().and_then(|rollback| rollback.get_rollback_tick()) else {return};
+
+    if counters.last_rollback_frame == Some(frame_count.0) {
+        return;
+    }
+
+    counters.last_rollback_frame = Some(frame_count.0);
+    counters.rollbacks += 1;
+    counters.rollback_depth_total += (tick_manager.tick() - rollback_tick).max(0) as u32;
+}
+
+pub fn sample_client_diagnostics(
+    mut counters: ResMut<ClientDiagnosticsCounters>,
+    mut diagnostics_log: ResMut<NetworkDiagnosticsLog>,
+    mut channel_stats: ResMut<ChannelStats>,
+    mut text_query: Query<&mut Text, With<NetworkDiagnosticsText>>,
+    connection_manager: Res<ConnectionManager>,
+    client_config: Res<ClientConfig>,
+    diagnostics_store: Res<DiagnosticsStore>,
+    position_corrections: Query<&Correction<Position>>,
+    rotation_corrections: Query<&Correction<Rotation>>,
+    settings: Res<NetworkDiagnosticsSettings>,
+    time: Res<Time>,
+){
+    counters.timer += time.delta_secs();
+    diagnostics_log.elapsed += time.delta_secs();
+
+    if counters.timer < settings.sample_interval {
+        return;
+    }
+
+    let interval = counters.timer;
+    let diagnostic_value = |path| diagnostics_store.get(path).and_then(|diagnostic| diagnostic.smoothed()).unwrap_or(0.0) as f32;
+    let sample = NetworkSample{
+        time: diagnostics_log.elapsed,
+        label: "client".to_string(),
+        rtt_ms: connection_manager.ping_manager.rtt().as_secs_f32() * 1000.0,
+        jitter_ms: connection_manager.ping_manager.jitter().as_secs_f32() * 1000.0,
+        input_delay_ticks: client_config.prediction.minimum_input_delay_ticks,
+        bytes_in_per_second: diagnostic_value(&IoDiagnosticsPlugin::BYTES_IN),
+        bytes_out_per_second: diagnostic_value(&IoDiagnosticsPlugin::BYTES_OUT),
+        rollbacks_per_second: counters.rollbacks as f32 / interval,
+        average_rollback_depth: if counters.rollbacks > 0 {counters.rollback_depth_total as f32 / counters.rollbacks as f32} else {0.0},
+        position_correction: position_corrections.iter()
+            .map(|correction| correction.original_prediction.0.distance(correction.final_correction_value.0))
+            .fold(0.0, f32::max),
+        rotation_correction: rotation_corrections.iter()
+            .map(|correction| correction.original_prediction.0.angle_between(correction.final_correction_value.0))
+            .fold(0.0, f32::max),
+        channels: channel_stats.sample(interval)
+    };
+
+    if let Ok(mut text) = text_query.get_single_mut() {
+        text.0 = sample.summary();
+    }
+
+    diagnostics_log.push(sample, settings.max_samples);
+    *counters = ClientDiagnosticsCounters{
+        last_rollback_frame: counters.last_rollback_frame,
+        ..default()
+    };
+}
+
+pub fn toggle_client_diagnostics(
+    keys: Res<ButtonInput<KeyCode>>,
+    mut settings: ResMut<NetworkDiagnosticsSettings>,
+    mut text_query: Query<&mut Visibility, With<NetworkDiagnosticsText>>,
+){
+    if keys.just_pressed(KeyCode::F3) {
+        settings.visible = !settings.visible;
+    }
+
+    for mut visibility in text_query.iter_mut() {
+        let target_visibility = if settings.visible {Visibility::Visible} else {Visibility::Hidden};
+
+        if *visibility != target_visibility {
+            *visibility = target_visibility;
+        }
+    }
+}
+
+pub fn export_client_diagnostics(
+    keys: Res<ButtonInput<KeyCode>>,
+    diagnostics_log: Res<NetworkDiagnosticsLog>,
+    settings: Res<NetworkDiagnosticsSettings>,
+){
+    if !keys.just_pressed(KeyCode::F4) {
+        return;
+    }
+
+    match diagnostics_log.write_csv(Path::new(&settings.export_path)) {
+        Ok(()) => log::info!("Exported {} network samples to {}", diagnostics_log.samples.len(), settings.export_path),
+        Err(error) => log::error!("Failed to export network diagnostics: {error}")
+    }
+}
@@ -0,0 +1,75 @@
+use bevy::math::Vec2;
+use bevy::prelude::{Query, Res, ResMut, Time, With};
+use leafwing_input_manager::prelude::ActionState;
+use lightyear::inputs::leafwing::input_buffer::InputBuffer;
+use lightyear::prelude::Tick;
+use lightyear::prelude::client::{ClientConfig, ConnectionManager};
+use shared::plugins::combatant::PlayerCombatant;
+use shared::plugins::shared::shared_configs;
+use shared::protocol::CharacterAction;
+use crate::plugins::latency::{AdaptiveLatencySettings, AdaptiveLatencyState};
+
+pub fn adapt_input_delay(
+    mut state: ResMut<AdaptiveLatencyState>,
+    mut client_config: ResMut<ClientConfig>,
+    settings: Res<AdaptiveLatencySettings>,
+    connection_manager: Res<ConnectionManager>,
+    action_query: Query<(&ActionState<CharacterAction>, Option<&InputBuffer<CharacterAction>>), With<PlayerCombatant>>,
+    time: Res<Time>,
+){
+    if !settings.enabled {
+        return;
+    }
+
+    state.timer += time.delta_secs();
+
+    if state.timer < settings.update_interval {
+        return;
+    }
+
+    state.timer = 0.0;
+
+    let tick_ms = shared_configs().tick.tick_duration.as_secs_f32() * 1000.0;
+    let rtt_ms = connection_manager.ping_manager.rtt().as_secs_f32() * 1000.0;
+    let jitter_ms = connection_manager.ping_manager.jitter().as_secs_f32() * 1000.0;
+    let min_delay = settings.min_input_delay_ticks.min(settings.max_input_delay_ticks);
+
+    let delayed_ms = rtt_ms * settings.delayed_rtt_share.clamp(0.0, 1.0) + jitter_ms * settings.jitter_margin;
+    state.target_input_delay_ticks = ((delayed_ms / tick_ms).round() as u16).clamp(min_delay, settings.max_input_delay_ticks);
+
+    let min_factor = settings.min_correction_ticks_factor.min(settings.max_correction_ticks_factor);
+    state.target_correction_ticks_factor = (min_factor + jitter_ms / tick_ms * settings.correction_jitter_weight)
+        .clamp(min_factor, settings.max_correction_ticks_factor);
+
+    let current_delay = client_config.prediction.minimum_input_delay_ticks;
+    let is_idle = action_query.iter().all(|(action_state, input_buffer)| {
+        is_idle_action(action_state) && input_buffer
+            .and_then(|input_buffer| input_buffer.get_last_with_tick().map(|(last_tick, _)| (input_buffer, last_tick)))
+            .is_none_or(|(input_buffer, last_tick)| {
+                (0..current_delay).all(|offset| input_buffer.get(Tick(last_tick.0.wrapping_sub(offset))).is_none_or(is_idle_action))
+            })
+    });
+
+    let next_delay = if state.target_input_delay_ticks > current_delay {
+        current_delay + 1
+    } else if state.target_input_delay_ticks < current_delay && is_idle {
+        current_delay - 1
+    } else {
+        current_delay
+    };
+
+    if next_delay != current_delay {
+        client_config.prediction.set_fixed_input_delay_ticks(next_delay);
+    }
+
+    let current_factor = client_config.prediction.correction_ticks_factor;
+    let factor_delta = (state.target_correction_ticks_factor - current_factor).clamp(-settings.correction_step, settings.correction_step);
+
+    if factor_delta != 0.0 {
+        client_config.prediction.correction_ticks_factor = current_factor + factor_delta;
+    }
+}
+
+fn is_idle_action(action_state: &ActionState<CharacterAction>) -> bool{
+    action_state.get_pressed().is_empty() && action_state.axis_pair(&CharacterAction::Move) == Vec2::ZERO
+}
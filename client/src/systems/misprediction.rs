@@ -0,0 +1,66 @@
+use std::collections::VecDeque;
+use std::fmt::Debug;
+use bevy::prelude::{Changed, Commands, Component, Entity, Query, Res, ResMut, With};
+use lightyear::prelude::client::{Confirmed, Predicted, Rollback};
+use lightyear::prelude::{Tick, TickManager};
+use crate::plugins::misprediction::{MispredictionCounts, MispredictionDiagnostics};
+
+const SNAPSHOT_TICKS: i16 = 128;
+
+#[derive(Component)]
+pub struct PredictedSnapshots<C>(pub VecDeque<(Tick, C)>);
+
+pub fn record_predicted_snapshots<C: Component + Clone>(
+    mut commands: Commands,
+    mut predicted_query: Query<(Entity, &C, Option<&mut PredictedSnapshots<C>>), With<Predicted>>,
+    tick_manager: Res<TickManager>,
+    rollback: Option<Res<Rollback>>,
+){
+    if rollback.as_ref().is_some_and(|rollback| rollback.is_rollback()) {
+        return;
+    }
+
+    let tick = tick_manager.tick();
+
+    for (entity, component, snapshots) in predicted_query.iter_mut() {
+        let Some(mut snapshots) = snapshots else {
+            commands.entity(entity).insert(PredictedSnapshots(VecDeque::from([(tick, component.clone())])));
+            continue;
+        };
+
+        snapshots.0.retain(|(snapshot_tick, _)| {
+            let age = tick - *snapshot_tick;
+
+            age > 0 && age < SNAPSHOT_TICKS
+        });
+        snapshots.0.push_back((tick, component.clone()));
+    }
+}
+
+pub fn compare_confirmed_values<C: Component + Clone + PartialEq + Debug>(
+    confirmed_query: Query<(&Confirmed, &C), Changed<C>>,
+    snapshot_query: Query<&PredictedSnapshots<C>>,
+    mut counts: ResMut<MispredictionCounts>,
+    diagnostics: Res<MispredictionDiagnostics>,
+){
+    let component_name = std::any::type_name::<C>().rsplit("::").next().unwrap_or_default();
+
+    for (confirmed, confirmed_value) in confirmed_query.iter() {
+        let Some(predicted) = confirmed.predicted else {continue};
+        let Ok(snapshots) = snapshot_query.get(predicted) else {continue};
+        let Some((_, predicted_value)) = snapshots.0.iter().find(|(snapshot_tick, _)| *snapshot_tick == confirmed.tick) else {continue};
+
+        if predicted_value == confirmed_value {
+            continue;
+        }
+
+        *counts.0.entry(component_name.to_string()).or_default() += 1;
+
+        if diagnostics.log_values {
+            log::warn!(
+                "Misprediction on {predicted:?} {component_name} at tick {:?}: predicted {predicted_value:?}, confirmed {confirmed_value:?}",
+                confirmed.tick
+            );
+        }
+    }
+}
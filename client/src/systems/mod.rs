@@ -1,2 +1,7 @@
 pub mod states;
-pub mod camera;
\ No newline at end of file
+pub mod camera;
+pub mod combat;
+pub mod diagnostics;
+pub mod misprediction;
+pub mod latency;
+pub mod chat;
\ No newline at end of file
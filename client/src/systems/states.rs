@@ -1,7 +1,9 @@
-use bevy::prelude::{Entity, EventReader, EventWriter, Query, With};
+use avian3d::prelude::Collider;
+use bevy::prelude::{Changed, Children, Entity, EventReader, EventWriter, Query, Transform, With};
 use shared::InteractNetworkAble;
-use shared::plugins::combatant::CombatantMarker;
+use shared::plugins::combatant::{CharacterController, CharacterControllerSettings, CombatantMarker, CombatantMeshBody};
 use shared::plugins::statesmachine::{StateAdded, States, StatesApplied};
+use shared::systems::charactercontroller::collider_half_height;
 use crate::plugins::animations::{AnimationsLoaded, PlayAnimation};
 
 pub fn check_idle_state(
@@ -41,3 +43,18 @@ pub fn check_walking_state(
         }
     }
 }
+
+pub fn update_combatant_mesh_height(
+    character_query: Query<(&Collider, &CharacterController, &CharacterControllerSettings, &Children), (With<CombatantMarker>, Changed<Collider>)>,
+    mut mesh_query: Query<&mut Transform, With<CombatantMeshBody>>
+){
+    for (collider, character_controller, settings, children) in character_query.iter(){
+        let half_height = collider_half_height(collider, settings, character_controller.crouched);
+
+        for child in children.iter(){
+            if let Ok(mut mesh_transform) = mesh_query.get_mut(*child) {
+                mesh_transform.translation.y = -(half_height + settings.float_height);
+            }
+        }
+    }
+}
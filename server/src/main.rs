@@ -1,13 +1,22 @@
 use avian3d::prelude::{Collider, RigidBody};
 use bevy::DefaultPlugins;
-use bevy::prelude::{default, App, Camera3d, Commands, Dir3, IntoSystemConfigs, PointLight, Startup, Transform, Vec3};
+use bevy::prelude::{default, App, Camera3d, Commands, Dir3, EventWriter, IntoSystemConfigs, PointLight, Startup, Transform, Vec3};
 use bevy_inspector_egui::quick::WorldInspectorPlugin;
 use lightyear::prelude::NetworkTarget;
 use lightyear::prelude::server::{Replicate, ReplicationTarget};
 use shared::GameMask;
+use shared::plugins::combatant::SpawnNpcCombatant;
+use shared::plugins::platforms::{MovingPlatform, MovingPlatformServerBundle};
+use shared::plugins::spawnpoints::SpawnPoint;
 use shared::protocol::{FloorMarker, REPLICATION_GROUP};
+use crate::plugins::ai::AiPlugin;
+use crate::plugins::chat::ChatPlugin;
 use crate::plugins::combatant::CombatantPlugin;
 use crate::plugins::connection::{start_server, ServerPlugin};
+use crate::plugins::diagnostics::ServerDiagnosticsPlugin;
+use crate::plugins::interest::InterestPlugin;
+use crate::plugins::navigation::NavigationPlugin;
+use crate::plugins::threat::ThreatPlugin;
 
 pub mod plugins;
 pub mod systems;
@@ -15,6 +24,7 @@ pub mod systems;
 
 fn default_stuff(
     mut commands: Commands,
+    mut spawn_npc_events: EventWriter<SpawnNpcCombatant>,
 ){
     commands.spawn((
         PointLight {
@@ -34,6 +44,7 @@ fn default_stuff(
         Collider::cylinder(50.0, 0.1),
         FloorMarker,
         GameMask::Floor,
+        GameMask::floor_layers(),
         Replicate{
             group: REPLICATION_GROUP,
             target: ReplicationTarget {
@@ -42,11 +53,34 @@ fn default_stuff(
             ..default()
         }
     ));
+
+    commands.spawn(MovingPlatformServerBundle::new(MovingPlatform{
+        travel: Vec3::new(0.0, 3.0, 0.0),
+        ..default()
+    }, Transform::from_xyz(-8.0, 0.1, 0.0)));
+
+    commands.spawn(MovingPlatformServerBundle::new(MovingPlatform{
+        size: Vec3::new(5.0, 0.2, 5.0),
+        angular_speed: 0.5,
+        ..default()
+    }, Transform::from_xyz(0.0, 0.1, -10.0)));
+
+    for translation in [Vec3::new(0.0, 0.85, 0.0), Vec3::new(4.0, 0.85, 0.0), Vec3::new(-4.0, 0.85, 0.0), Vec3::new(0.0, 0.85, 4.0), Vec3::new(0.0, 0.85, -4.0)] {
+        commands.spawn((
+            SpawnPoint::default(),
+            Transform::from_translation(translation),
+        ));
+    }
+
+    spawn_npc_events.send(SpawnNpcCombatant{
+        transform: Some(Transform::from_xyz(8.0, 0.85, 8.0)),
+        ..default()
+    });
 }
 
 fn main() {
     App::new()
-        .add_plugins((DefaultPlugins,WorldInspectorPlugin::new(),ServerPlugin, CombatantPlugin))
+        .add_plugins((DefaultPlugins,WorldInspectorPlugin::new(),ServerPlugin, CombatantPlugin, AiPlugin, NavigationPlugin, ThreatPlugin, InterestPlugin, ServerDiagnosticsPlugin, ChatPlugin))
         .add_systems(Startup,default_stuff.after(start_server))
         .run();
 }
@@ -0,0 +1,164 @@
+use std::fmt::{Display, Formatter};
+use bevy::app::{App, FixedUpdate, Plugin};
+use bevy::asset::io::Reader;
+use bevy::asset::{Asset, AssetApp, AssetLoader, AssetServer, Handle, LoadContext};
+use bevy::math::Vec3;
+use bevy::prelude::{Commands, Component, Entity, IntoSystemConfigs, Query, Reflect, ReflectComponent, Res, Resource, With, Without};
+use serde::Deserialize;
+use shared::plugins::combatant::NpcCombatant;
+use crate::systems::behaviourtree::{apply_npc_outputs, run_npc_behaviour_trees, select_npc_targets};
+
+pub struct AiPlugin;
+
+#[derive(Resource)]
+pub struct AiSettings{
+    pub default_tree: String
+}
+
+#[derive(Asset, Deserialize, Clone, Debug, Reflect)]
+pub struct BehaviourTree{
+    pub root: BehaviourNode
+}
+
+#[derive(Deserialize, Clone, Debug, Reflect)]
+pub enum BehaviourNode{
+    Selector(Vec<BehaviourNode>),
+    Sequence(Vec<BehaviourNode>),
+    Condition(AiCondition),
+    Action(AiAction)
+}
+
+#[derive(Deserialize, Clone, Debug, PartialEq, Reflect)]
+pub enum AiCondition{
+    HasTarget,
+    TargetWithin(f32),
+    TargetBeyond(f32),
+    HealthBelow(f32)
+}
+
+#[derive(Deserialize, Clone, Debug, PartialEq, Reflect)]
+pub enum AiAction{
+    Idle,
+    Wander(f32),
+    Chase,
+    KeepDistance(f32),
+    Attack,
+    Block,
+    Flee
+}
+
+#[derive(Component, Reflect)]
+#[reflect(Component)]
+pub struct NpcBrain{
+    pub tree: Handle<BehaviourTree>,
+    pub target: Option<Entity>,
+    pub sight_range: f32,
+    pub attack_cooldown_ticks: u16,
+    pub active_action: Option<AiAction>,
+    pub home: Option<Vec3>,
+    pub wander_goal: Option<Vec3>,
+    pub last_attack_tick: Option<u16>,
+    pub rng_state: u64
+}
+
+#[derive(Component, Clone, Default, Reflect)]
+#[reflect(Component)]
+pub struct NpcOutput{
+    pub move_direction: Vec3,
+    pub move_goal: Option<Vec3>,
+    pub attack: bool,
+    pub block: bool
+}
+
+#[derive(Default)]
+pub struct BehaviourTreeLoader;
+
+#[derive(Debug)]
+pub enum BehaviourTreeLoaderError{
+    Io(std::io::Error),
+    Ron(ron::error::SpannedError)
+}
+
+impl Plugin for AiPlugin {
+    fn build(&self, app: &mut App) {
+        app.init_asset::<BehaviourTree>();
+        app.register_asset_reflect::<BehaviourTree>();
+        app.init_asset_loader::<BehaviourTreeLoader>();
+        app.register_type::<NpcBrain>();
+        app.register_type::<NpcOutput>();
+        app.insert_resource(AiSettings{
+            default_tree: "ai/default.bt.ron".to_string()
+        });
+        app.add_systems(FixedUpdate,(attach_npc_brain,select_npc_targets,run_npc_behaviour_trees,apply_npc_outputs).chain());
+    }
+}
+
+impl NpcBrain {
+    pub fn new(tree: Handle<BehaviourTree>, seed: u64) -> Self{
+        Self{
+            tree,
+            target: None,
+            sight_range: 15.0,
+            attack_cooldown_ticks: 51,
+            active_action: None,
+            home: None,
+            wander_goal: None,
+            last_attack_tick: None,
+            rng_state: seed.max(1)
+        }
+    }
+
+    pub fn next_random(&mut self) -> f32{
+        self.rng_state ^= self.rng_state << 13;
+        self.rng_state ^= self.rng_state >> 7;
+        self.rng_state ^= self.rng_state << 17;
+        (self.rng_state % 10_000) as f32 / 10_000.0
+    }
+}
+
+impl AssetLoader for BehaviourTreeLoader {
+    type Asset = BehaviourTree;
+    type Settings = ();
+    type Error = BehaviourTreeLoaderError;
+
+    async fn load(
+        &self,
+        reader: &mut dyn Reader,
+        _settings: &(),
+        _load_context: &mut LoadContext<'_>,
+    ) -> Result<Self::Asset, Self::Error> {
+        let mut bytes = Vec::new();
+        reader.read_to_end(&mut bytes).await.map_err(BehaviourTreeLoaderError::Io)?;
+
+        ron::de::from_bytes::<BehaviourTree>(&bytes).map_err(BehaviourTreeLoaderError::Ron)
+    }
+
+    fn extensions(&self) -> &[&str] {
+        &["bt.ron"]
+    }
+}
+
+impl Display for BehaviourTreeLoaderError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            BehaviourTreeLoaderError::Io(error) => write!(f, "could not read behaviour tree: {error}"),
+            BehaviourTreeLoaderError::Ron(error) => write!(f, "could not parse behaviour tree: {error}")
+        }
+    }
+}
+
+impl std::error::Error for BehaviourTreeLoaderError {}
+
+fn attach_npc_brain(
+    mut commands: Commands,
+    npc_query: Query<Entity, (With<NpcCombatant>, Without<NpcBrain>)>,
+    asset_server: Res<AssetServer>,
+    ai_settings: Res<AiSettings>,
+){
+    for entity in npc_query.iter() {
+        commands.entity(entity).insert((
+            NpcBrain::new(asset_server.load(&ai_settings.default_tree), entity.to_bits()),
+            NpcOutput::default(),
+        ));
+    }
+}
@@ -0,0 +1,130 @@
+use std::collections::VecDeque;
+use bevy::app::{App, Plugin, Update};
+use bevy::prelude::{IntoSystemConfigs, Reflect, ReflectResource, Resource};
+use bevy::utils::hashbrown::{HashMap, HashSet};
+use lightyear::prelude::ClientId;
+use shared::protocol::MAX_CHAT_LENGTH;
+use crate::systems::chat::{receive_chat_mutes, receive_chat_requests, remove_disconnected_chat};
+
+pub struct ChatPlugin;
+
+#[derive(Resource, Clone, Debug, Reflect)]
+#[reflect(Resource)]
+pub struct ChatSettings{
+    pub max_length: usize,
+    pub rate_limit_messages: usize,
+    pub rate_limit_window: f32,
+    pub proximity_radius: f32,
+    pub filter_profanity: bool,
+    pub profanity_words: Vec<String>,
+    pub muted_clients: Vec<u64>
+}
+
+#[derive(Resource, Default)]
+pub struct ChatState{
+    pub recent_messages: HashMap<ClientId,VecDeque<f32>>,
+    pub mutes: HashMap<ClientId,HashSet<ClientId>>
+}
+
+impl Default for ChatSettings {
+    fn default() -> Self {
+        Self {
+            max_length: MAX_CHAT_LENGTH,
+            rate_limit_messages: 5,
+            rate_limit_window: 5.0,
+            proximity_radius: 20.0,
+            filter_profanity: true,
+            profanity_words: ["damn", "hell", "crap"].into_iter().map(String::from).collect(),
+            muted_clients: Vec::new()
+        }
+    }
+}
+
+impl ChatSettings {
+    pub fn filter(&self, text: &str) -> String{
+        if !self.filter_profanity {
+            return text.to_string();
+        }
+
+        text.split(' ')
+            .map(|word| {
+                let normalized: String = word.chars().filter(|character| character.is_alphanumeric()).collect::<String>().to_lowercase();
+
+                if self.profanity_words.iter().any(|profanity| profanity.to_lowercase() == normalized) {
+                    "*".repeat(word.chars().count())
+                }else {
+                    word.to_string()
+                }
+            })
+            .collect::<Vec<String>>()
+            .join(" ")
+    }
+}
+
+impl ChatState {
+    pub fn allow(&mut self, client_id: ClientId, now: f32, settings: &ChatSettings) -> bool{
+        let recent = self.recent_messages.entry(client_id).or_default();
+
+        while recent.front().is_some_and(|time| now - time > settings.rate_limit_window) {
+            recent.pop_front();
+        }
+
+        if recent.len() >= settings.rate_limit_messages {
+            return false;
+        }
+
+        recent.push_back(now);
+        true
+    }
+
+    pub fn is_muted_by(&self, recipient: ClientId, sender: ClientId) -> bool{
+        self.mutes.get(&recipient).is_some_and(|muted| muted.contains(&sender))
+    }
+}
+
+impl Plugin for ChatPlugin {
+    fn build(&self, app: &mut App) {
+        app.register_type::<ChatSettings>();
+        app.init_resource::<ChatSettings>();
+        app.init_resource::<ChatState>();
+        app.add_systems(Update,(receive_chat_mutes,receive_chat_requests,remove_disconnected_chat).chain());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rate_limits_within_window() {
+        let settings = ChatSettings::default();
+        let mut chat_state = ChatState::default();
+        let client_id = ClientId::Netcode(1);
+
+        for message in 0..settings.rate_limit_messages {
+            assert!(chat_state.allow(client_id, message as f32 * 0.1, &settings));
+        }
+
+        assert!(!chat_state.allow(client_id, 1.0, &settings));
+        assert!(chat_state.allow(ClientId::Netcode(2), 1.0, &settings));
+        assert!(chat_state.allow(client_id, settings.rate_limit_window + 0.05, &settings));
+    }
+
+    #[test]
+    fn masks_profanity_case_insensitively() {
+        let settings = ChatSettings::default();
+
+        assert_eq!(settings.filter("Damn, that was close"), "***** that was close");
+        assert_eq!(settings.filter("hello there"), "hello there");
+    }
+
+    #[test]
+    fn leaves_text_unfiltered_when_disabled() {
+        let settings = ChatSettings{
+            filter_profanity: false,
+            ..ChatSettings::default()
+        };
+
+        assert_eq!(settings.filter("damn"), "damn");
+    }
+}
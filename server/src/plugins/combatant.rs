@@ -1,27 +1,92 @@
+use avian3d::prelude::{Collider, Position, Rotation, SpatialQuery, SpatialQueryFilter};
 use bevy::app::{App, PostUpdate};
-use bevy::prelude::{IntoSystemConfigs, Plugin, Query, TransformSystem, With};
+use bevy::math::Vec3;
+use bevy::prelude::{Entity, EventReader, EventWriter, IntoSystemConfigs, Plugin, Query, Res, TransformSystem, Update, With};
 use leafwing_input_manager::action_state::ActionState;
-use shared::InteractNetworkAble;
+use lightyear::prelude::TickManager;
+use lightyear::prelude::server::ServerReceiveMessage;
+use shared::plugins::knockback::{is_stunned, Knockback};
+use shared::{GameMask, InteractNetworkAble};
+use shared::plugins::combatant::{CharacterControllerSettings, CombatSettings, Stamina};
+use shared::plugins::health::DamageEvent;
 use shared::plugins::statesmachine::{CurrentStates, StatesApplied};
-use shared::protocol::CharacterAction;
-use shared::systems::characteractions::move_action;
+use shared::protocol::{CharacterAction, CosmeticChannel, CosmeticEventMessage, ServerMessages};
+use shared::systems::characteractions::{block_action, can_attack, crouch_action, move_action, sprint_action};
 
 pub struct CombatantPlugin;
 
 impl Plugin for CombatantPlugin {
     fn build(&self, app: &mut App) {
-        app.add_systems(PostUpdate,handle_combatant_actions.before(TransformSystem::TransformPropagate));
+        app.add_systems(PostUpdate,(handle_combatant_actions,handle_combatant_attacks).chain().before(TransformSystem::TransformPropagate));
+        app.add_systems(Update,relay_cosmetic_events);
     }
 }
 
 pub fn handle_combatant_actions(
-    mut query: Query<(&ActionState<CharacterAction>, &mut CurrentStates),(With<InteractNetworkAble>, With<StatesApplied>)>,
+    mut query: Query<(&ActionState<CharacterAction>, &CharacterControllerSettings, &Stamina, Option<&Knockback>, &mut CurrentStates),(With<InteractNetworkAble>, With<StatesApplied>)>,
+    tick_manager: Res<TickManager>,
 ){
-    for (action_state, mut current_states) in &mut query {
+    let tick = tick_manager.tick();
+
+    for (action_state, settings, stamina, knockback, mut current_states) in &mut query {
+        if is_stunned(knockback, tick) {
+            continue;
+        }
+
         let move_dir = action_state
             .axis_pair(&CharacterAction::Move)
             .clamp_length_max(1.0);
 
+        block_action(action_state.pressed(&CharacterAction::Block), tick, &mut current_states);
         move_action(move_dir, &mut current_states);
+        crouch_action(action_state.pressed(&CharacterAction::Crouch), action_state.just_pressed(&CharacterAction::Crouch), settings.crouch_toggle, &mut current_states);
+        sprint_action(action_state.pressed(&CharacterAction::Sprint), stamina, &mut current_states);
     }
-}
\ No newline at end of file
+}
+
+pub fn handle_combatant_attacks(
+    query: Query<(Entity, &ActionState<CharacterAction>, &CurrentStates, Option<&Knockback>, &Position, &Rotation),(With<InteractNetworkAble>, With<StatesApplied>)>,
+    spatial_query: SpatialQuery,
+    tick_manager: Res<TickManager>,
+    combat_settings: Res<CombatSettings>,
+    mut damage_events: EventWriter<DamageEvent>,
+){
+    let tick = tick_manager.tick();
+
+    for (entity, action_state, current_states, knockback, position, rotation) in query.iter() {
+        if !action_state.just_pressed(&CharacterAction::Attack) || !can_attack(current_states) || is_stunned(knockback, tick) {
+            continue;
+        }
+
+        let facing = (rotation.0 * Vec3::Z).with_y(0.0).normalize_or_zero();
+        let hit_center = position.0 + facing * combat_settings.melee_range;
+
+        for target in spatial_query.shape_intersections(
+            &Collider::sphere(combat_settings.melee_radius),
+            hit_center,
+            rotation.0,
+            &SpatialQueryFilter::from_mask(GameMask::Combatant).with_excluded_entities([entity])
+        ) {
+            damage_events.send(DamageEvent{
+                target,
+                attacker: Some(entity),
+                amount: combat_settings.melee_damage
+            });
+        }
+    }
+}
+
+pub fn relay_cosmetic_events(
+    mut cosmetic_messages: EventReader<ServerReceiveMessage<CosmeticEventMessage>>,
+    mut messages: ServerMessages,
+){
+    for event in cosmetic_messages.read() {
+        let client_id = event.from();
+        let Some(source) = messages.client_combatant(client_id) else {continue};
+
+        messages.send_to_all_except::<CosmeticChannel, CosmeticEventMessage>(client_id, &mut CosmeticEventMessage{
+            source: Some(source),
+            ..event.message().clone()
+        });
+    }
+}
@@ -1,10 +1,11 @@
 use bevy::app::{App, Plugin};
-use bevy::prelude::{Commands, Startup};
+use bevy::log::info;
+use bevy::prelude::{Commands, Local, Res, ResMut, Startup, Time, Update};
 use bevy::utils::default;
 use lightyear::connection::server::{IoConfig, NetConfig};
 use lightyear::prelude::server::{NetcodeConfig, ServerCommandsExt, ServerConfig, ServerPlugins, ServerTransport};
 use shared::NetworkSide;
-use shared::plugins::shared::{settings, shared_configs, SharedPlugin, PRIVATE_KEY, PROTOCOL_ID, SERVER_ADDR};
+use shared::plugins::shared::{link_conditioner_settled, protocol_id, settings, shared_configs, LinkConditionerSettings, Settings, SharedPlugin, PRIVATE_KEY, SERVER_ADDR};
 
 pub struct ServerPlugin;
 
@@ -12,22 +13,27 @@ impl Plugin for ServerPlugin {
     fn build(&self, app: &mut App) {
         let settings = settings();
 
-        app.add_plugins((setup_server_plugins(),SharedPlugin{
+        app.add_plugins((setup_server_plugins(&settings),SharedPlugin{
             predict_all: settings.predict_all,
+            compact_combatant_state: settings.compact_combatant_state,
             network_side: NetworkSide::Server
         }));
+        app.register_type::<LinkConditionerSettings>();
+        app.insert_resource(settings.link_conditioner);
         app.add_systems(Startup,start_server);
+        app.add_systems(Update,apply_link_conditioner);
     }
 }
 
-fn setup_net_config() -> NetConfig{
+fn setup_net_config(settings: &Settings) -> NetConfig{
     let io_config = IoConfig{
         transport: ServerTransport::UdpSocket(SERVER_ADDR),
+        conditioner: settings.link_conditioner.config(),
         ..default()
     };
 
     let netcode_config = NetcodeConfig::default()
-        .with_protocol_id(PROTOCOL_ID)
+        .with_protocol_id(protocol_id(settings.compact_combatant_state))
         .with_key(PRIVATE_KEY);
 
 
@@ -37,14 +43,32 @@ fn setup_net_config() -> NetConfig{
     }
 }
 
-fn setup_server_plugins() -> ServerPlugins {
+fn setup_server_plugins(settings: &Settings) -> ServerPlugins {
     ServerPlugins::new(ServerConfig{
         shared: shared_configs(),
-        net: vec![setup_net_config()],
+        net: vec![setup_net_config(settings)],
         ..default()
     })
 }
 
 pub fn start_server(mut commands: Commands) {
     commands.start_server();
-}
\ No newline at end of file
+}
+fn apply_link_conditioner(
+    mut pending_since: Local<Option<f32>>,
+    link_conditioner: Res<LinkConditionerSettings>,
+    mut server_config: ResMut<ServerConfig>,
+    time: Res<Time>,
+){
+    if !link_conditioner_settled(&link_conditioner, &mut pending_since, &time) {
+        return;
+    }
+
+    for net_config in server_config.net.iter_mut() {
+        if let NetConfig::Netcode{io, ..} = net_config {
+            io.conditioner = link_conditioner.config();
+        }
+    }
+
+    info!("Link conditioner set to {:?}, applied when the server restarts", *link_conditioner);
+}
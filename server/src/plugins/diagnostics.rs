@@ -0,0 +1,20 @@
+use bevy::app::{App, Plugin, Startup, Update};
+use bevy::prelude::{IntoSystemConfigs, Resource};
+use shared::plugins::diagnostics::NetworkDiagnosticsPlugin;
+use crate::systems::diagnostics::{export_server_diagnostics, sample_server_diagnostics, spawn_server_diagnostics_panel, toggle_server_diagnostics};
+
+pub struct ServerDiagnosticsPlugin;
+
+#[derive(Resource, Default)]
+pub struct ServerDiagnosticsCounters{
+    pub timer: f32
+}
+
+impl Plugin for ServerDiagnosticsPlugin {
+    fn build(&self, app: &mut App) {
+        app.add_plugins(NetworkDiagnosticsPlugin);
+        app.init_resource::<ServerDiagnosticsCounters>();
+        app.add_systems(Startup,spawn_server_diagnostics_panel);
+        app.add_systems(Update,(sample_server_diagnostics,toggle_server_diagnostics,export_server_diagnostics).chain());
+    }
+}
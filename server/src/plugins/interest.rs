@@ -0,0 +1,74 @@
+use bevy::app::{App, Plugin, PostUpdate};
+use bevy::math::{IVec2, Vec3};
+use bevy::prelude::{Component, IntoSystemConfigs, Reflect, ReflectComponent, ReflectResource, Resource};
+use bevy::utils::hashbrown::{HashMap, HashSet};
+use lightyear::prelude::ClientId;
+use lightyear::prelude::server::RoomId;
+use crate::systems::interest::{attach_interest_cells, remove_disconnected_interest, update_client_interest, update_interest_cells};
+
+pub struct InterestPlugin;
+
+#[derive(Resource, Clone, Debug, Reflect)]
+#[reflect(Resource)]
+pub struct InterestSettings{
+    pub cell_size: f32,
+    pub view_radius_cells: i32,
+    pub hysteresis: f32
+}
+
+#[derive(Component, Clone, Copy, Debug, PartialEq, Eq, Reflect)]
+#[reflect(Component)]
+pub struct InterestCell(pub IVec2);
+
+#[derive(Resource, Default)]
+pub struct ClientInterest(pub HashMap<ClientId,HashSet<RoomId>>);
+
+impl Default for InterestSettings {
+    fn default() -> Self {
+        Self {
+            cell_size: 16.0,
+            view_radius_cells: 1,
+            hysteresis: 2.0
+        }
+    }
+}
+
+impl Plugin for InterestPlugin {
+    fn build(&self, app: &mut App) {
+        app.register_type::<InterestSettings>();
+        app.register_type::<InterestCell>();
+        app.init_resource::<InterestSettings>();
+        app.init_resource::<ClientInterest>();
+        app.add_systems(PostUpdate,(attach_interest_cells,update_interest_cells,update_client_interest,remove_disconnected_interest).chain());
+    }
+}
+
+impl InterestSettings {
+    pub fn cell_of(&self, position: Vec3) -> IVec2{
+        IVec2::new(
+            (position.x / self.cell_size).floor() as i32,
+            (position.z / self.cell_size).floor() as i32
+        )
+    }
+
+    pub fn next_cell(&self, cell: IVec2, position: Vec3) -> Option<IVec2>{
+        let min = cell.as_vec2() * self.cell_size - self.hysteresis;
+        let max = (cell + IVec2::ONE).as_vec2() * self.cell_size + self.hysteresis;
+        let inside = position.x >= min.x && position.x < max.x && position.z >= min.y && position.z < max.y;
+
+        if inside {None} else {Some(self.cell_of(position))}
+    }
+
+    pub fn rooms_around(&self, cell: IVec2) -> HashSet<RoomId>{
+        let radius = self.view_radius_cells.max(0);
+
+        (-radius..=radius)
+            .flat_map(|x| (-radius..=radius).map(move |z| cell + IVec2::new(x, z)))
+            .map(room_id)
+            .collect()
+    }
+}
+
+pub fn room_id(cell: IVec2) -> RoomId{
+    RoomId(((cell.x as u32 as u64) << 32) | (cell.y as u32 as u64))
+}
@@ -1,2 +1,8 @@
 pub mod connection;
-pub mod combatant;
\ No newline at end of file
+pub mod combatant;
+pub mod ai;
+pub mod navigation;
+pub mod threat;
+pub mod interest;
+pub mod diagnostics;
+pub mod chat;
\ No newline at end of file
@@ -0,0 +1,342 @@
+use std::cmp::Ordering;
+use std::collections::BinaryHeap;
+use bevy::app::{App, FixedUpdate, Plugin, Update};
+use bevy::math::{IVec2, Vec3};
+use bevy::prelude::{Component, IntoSystemConfigs, Reflect, ReflectComponent, ReflectResource, Resource};
+use crate::systems::behaviourtree::{apply_npc_outputs, run_npc_behaviour_trees};
+use crate::systems::navigation::{attach_nav_agents, build_navmesh, draw_navmesh_debug, mark_navmesh_dirty, steer_nav_agents, toggle_navmesh_debug};
+
+const DIAGONAL_COST: f32 = std::f32::consts::SQRT_2;
+
+pub struct NavigationPlugin;
+
+#[derive(Resource, Clone, Debug, Reflect)]
+#[reflect(Resource)]
+pub struct NavMeshSettings{
+    pub cell_size: f32,
+    pub half_extent: f32,
+    pub max_height: f32,
+    pub agent_radius: f32,
+    pub agent_height: f32,
+    pub max_step_height: f32,
+    pub avoidance_radius: f32,
+    pub avoidance_strength: f32
+}
+
+#[derive(Resource, Default, Reflect)]
+#[reflect(Resource)]
+pub struct NavMeshDebug(pub bool);
+
+#[derive(Resource, Default)]
+pub struct NavMesh{
+    pub origin: Vec3,
+    pub cell_size: f32,
+    pub width: i32,
+    pub depth: i32,
+    pub cells: Vec<Option<f32>>,
+    pub max_step_height: f32,
+    pub rebuild_in_frames: Option<u8>
+}
+
+#[derive(Component, Default, Reflect)]
+#[reflect(Component)]
+pub struct NavAgent{
+    pub goal: Option<Vec3>,
+    pub path: Vec<Vec3>,
+    pub repath_timer: f32,
+    pub failed_attempts: u8
+}
+
+#[derive(PartialEq)]
+struct OpenNode{
+    cost: f32,
+    cell: IVec2
+}
+
+impl Default for NavMeshSettings {
+    fn default() -> Self {
+        Self {
+            cell_size: 0.5,
+            half_extent: 25.0,
+            max_height: 20.0,
+            agent_radius: 0.3,
+            agent_height: 1.6,
+            max_step_height: 0.3,
+            avoidance_radius: 1.2,
+            avoidance_strength: 1.0
+        }
+    }
+}
+
+impl Plugin for NavigationPlugin {
+    fn build(&self, app: &mut App) {
+        app.register_type::<NavMeshSettings>();
+        app.register_type::<NavMeshDebug>();
+        app.register_type::<NavAgent>();
+        app.init_resource::<NavMeshSettings>();
+        app.init_resource::<NavMeshDebug>();
+        app.insert_resource(NavMesh{
+            rebuild_in_frames: Some(2),
+            ..Default::default()
+        });
+        app.add_systems(Update,(mark_navmesh_dirty,build_navmesh).chain());
+        app.add_systems(FixedUpdate,(attach_nav_agents,steer_nav_agents).chain().after(run_npc_behaviour_trees).before(apply_npc_outputs));
+        app.add_systems(Update,(toggle_navmesh_debug,draw_navmesh_debug).chain());
+    }
+}
+
+impl Eq for OpenNode {}
+
+impl Ord for OpenNode {
+    fn cmp(&self, other: &Self) -> Ordering {
+        other.cost.total_cmp(&self.cost)
+    }
+}
+
+impl PartialOrd for OpenNode {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl NavMesh {
+    pub fn cell_of(&self, position: Vec3) -> IVec2{
+        IVec2::new(
+            ((position.x - self.origin.x) / self.cell_size).floor() as i32,
+            ((position.z - self.origin.z) / self.cell_size).floor() as i32
+        )
+    }
+
+    pub fn cell_center(&self, cell: IVec2) -> Option<Vec3>{
+        let height = self.height(cell)?;
+
+        Some(Vec3::new(
+            self.origin.x + (cell.x as f32 + 0.5) * self.cell_size,
+            height,
+            self.origin.z + (cell.y as f32 + 0.5) * self.cell_size
+        ))
+    }
+
+    pub fn height(&self, cell: IVec2) -> Option<f32>{
+        if cell.x < 0 || cell.y < 0 || cell.x >= self.width || cell.y >= self.depth {
+            return None;
+        }
+
+        self.cells[(cell.y * self.width + cell.x) as usize]
+    }
+
+    pub fn is_walkable(&self, cell: IVec2) -> bool{
+        self.height(cell).is_some()
+    }
+
+    fn can_step(&self, from: IVec2, to: IVec2) -> bool{
+        match (self.height(from), self.height(to)) {
+            (Some(from_height), Some(to_height)) => (to_height - from_height).abs() <= self.max_step_height,
+            _ => false
+        }
+    }
+
+    fn nearest_walkable(&self, cell: IVec2, search_radius: i32) -> Option<IVec2>{
+        if self.is_walkable(cell) {
+            return Some(cell);
+        }
+
+        (1..=search_radius).find_map(|radius| {
+            (-radius..=radius)
+                .flat_map(|x| (-radius..=radius).map(move |y| cell + IVec2::new(x, y)))
+                .filter(|candidate| self.is_walkable(*candidate))
+                .min_by_key(|candidate| (*candidate - cell).length_squared())
+        })
+    }
+
+    pub fn find_path(&self, start: Vec3, goal: Vec3) -> Option<Vec<Vec3>>{
+        let start_cell = self.nearest_walkable(self.cell_of(start), 3)?;
+        let goal_cell = self.nearest_walkable(self.cell_of(goal), 3)?;
+        let cell_count = (self.width * self.depth) as usize;
+        let index = |cell: IVec2| (cell.y * self.width + cell.x) as usize;
+        let heuristic = |cell: IVec2| (goal_cell - cell).as_vec2().length();
+
+        let mut costs = vec![f32::MAX; cell_count];
+        let mut came_from: Vec<Option<IVec2>> = vec![None; cell_count];
+        let mut open = BinaryHeap::new();
+
+        costs[index(start_cell)] = 0.0;
+        open.push(OpenNode{cost: heuristic(start_cell), cell: start_cell});
+
+        while let Some(OpenNode{cell, ..}) = open.pop() {
+            if cell == goal_cell {
+                let mut cells = vec![cell];
+
+                while let Some(previous) = came_from[index(*cells.last().unwrap())] {
+                    cells.push(previous);
+                }
+
+                cells.reverse();
+
+                return Some(self.smooth_path(&cells, goal));
+            }
+
+            for x in -1..=1 {
+                for y in -1..=1 {
+                    let offset = IVec2::new(x, y);
+
+                    if offset == IVec2::ZERO {
+                        continue;
+                    }
+
+                    let neighbour = cell + offset;
+                    let is_diagonal = x != 0 && y != 0;
+
+                    if !self.can_step(cell, neighbour) {
+                        continue;
+                    }
+
+                    if is_diagonal && (!self.is_walkable(cell + IVec2::new(x, 0)) || !self.is_walkable(cell + IVec2::new(0, y))) {
+                        continue;
+                    }
+
+                    let cost = costs[index(cell)] + if is_diagonal {DIAGONAL_COST} else {1.0};
+
+                    if cost < costs[index(neighbour)] {
+                        costs[index(neighbour)] = cost;
+                        came_from[index(neighbour)] = Some(cell);
+                        open.push(OpenNode{cost: cost + heuristic(neighbour), cell: neighbour});
+                    }
+                }
+            }
+        }
+
+        None
+    }
+
+    fn smooth_path(&self, cells: &[IVec2], goal: Vec3) -> Vec<Vec3>{
+        let mut waypoints = Vec::new();
+        let mut anchor = 0;
+
+        while anchor < cells.len() - 1 {
+            let mut furthest = anchor + 1;
+
+            for candidate in (anchor + 2)..cells.len() {
+                if self.has_line_of_sight(cells[anchor], cells[candidate]) {
+                    furthest = candidate;
+                }
+            }
+
+            if let Some(center) = self.cell_center(cells[furthest]) {
+                waypoints.push(center);
+            }
+
+            anchor = furthest;
+        }
+
+        if let Some(last) = waypoints.last_mut() {
+            *last = goal.with_y(last.y);
+        }
+
+        waypoints
+    }
+
+    fn has_line_of_sight(&self, from: IVec2, to: IVec2) -> bool{
+        let delta = to - from;
+        let steps = delta.x.abs().max(delta.y.abs()) * 2;
+        let mut previous = from;
+
+        for step in 1..=steps {
+            let t = step as f32 / steps as f32;
+            let point = from.as_vec2() + delta.as_vec2() * t;
+            let cell = point.round().as_ivec2();
+
+            if cell != previous {
+                if !self.can_step(previous, cell) {
+                    return false;
+                }
+
+                previous = cell;
+            }
+        }
+
+        true
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn flat_grid(width: i32, depth: i32) -> NavMesh{
+        NavMesh{
+            origin: Vec3::ZERO,
+            cell_size: 1.0,
+            width,
+            depth,
+            cells: vec![Some(0.0); (width * depth) as usize],
+            max_step_height: 0.5,
+            rebuild_in_frames: None
+        }
+    }
+
+    fn set_cell(nav_mesh: &mut NavMesh, cell: IVec2, height: Option<f32>){
+        let index = (cell.y * nav_mesh.width + cell.x) as usize;
+
+        nav_mesh.cells[index] = height;
+    }
+
+    #[test]
+    fn finds_straight_path_on_open_grid() {
+        let nav_mesh = flat_grid(10, 10);
+        let goal = Vec3::new(9.5, 0.0, 0.5);
+
+        let path = nav_mesh.find_path(Vec3::new(0.5, 0.0, 0.5), goal).unwrap();
+
+        assert_eq!(path.len(), 1);
+        assert_eq!(path[0], goal);
+    }
+
+    #[test]
+    fn routes_around_walls_through_gap() {
+        let mut nav_mesh = flat_grid(10, 10);
+
+        for y in 0..9 {
+            set_cell(&mut nav_mesh, IVec2::new(5, y), None);
+        }
+
+        let goal = Vec3::new(9.5, 0.0, 0.5);
+        let path = nav_mesh.find_path(Vec3::new(0.5, 0.0, 0.5), goal).unwrap();
+
+        assert!(path.iter().any(|waypoint| waypoint.z > 9.0));
+        assert_eq!(*path.last().unwrap(), goal);
+    }
+
+    #[test]
+    fn returns_none_when_goal_is_unreachable() {
+        let mut nav_mesh = flat_grid(10, 10);
+
+        for y in 0..10 {
+            set_cell(&mut nav_mesh, IVec2::new(5, y), None);
+        }
+
+        assert!(nav_mesh.find_path(Vec3::new(0.5, 0.0, 0.5), Vec3::new(9.5, 0.0, 0.5)).is_none());
+    }
+
+    #[test]
+    fn refuses_steps_higher_than_max_step_height() {
+        let mut nav_mesh = flat_grid(10, 10);
+
+        for y in 0..10 {
+            for x in 5..10 {
+                set_cell(&mut nav_mesh, IVec2::new(x, y), Some(2.0));
+            }
+        }
+
+        assert!(nav_mesh.find_path(Vec3::new(0.5, 0.0, 0.5), Vec3::new(9.5, 2.0, 0.5)).is_none());
+    }
+
+    #[test]
+    fn snaps_blocked_start_to_nearest_walkable_cell() {
+        let mut nav_mesh = flat_grid(10, 10);
+
+        set_cell(&mut nav_mesh, IVec2::new(0, 0), None);
+
+        assert!(nav_mesh.find_path(Vec3::new(0.5, 0.0, 0.5), Vec3::new(9.5, 0.0, 9.5)).is_some());
+    }
+}
@@ -0,0 +1,104 @@
+use bevy::app::{App, FixedUpdate, Plugin};
+use bevy::prelude::{Component, Entity, IntoSystemConfigs, Reflect, ReflectComponent, ReflectResource, Resource};
+use bevy::utils::hashbrown::HashMap;
+use crate::systems::behaviourtree::select_npc_targets;
+use crate::systems::threat::{attach_threat_tables, clear_invalid_threat, decay_threat, raise_threat_from_damage, raise_threat_from_healing, raise_threat_from_proximity};
+
+pub struct ThreatPlugin;
+
+#[derive(Resource, Clone, Debug, Reflect)]
+#[reflect(Resource)]
+pub struct ThreatSettings{
+    pub damage_multiplier: f32,
+    pub healing_multiplier: f32,
+    pub proximity_radius: f32,
+    pub proximity_per_second: f32,
+    pub decay_per_second: f32
+}
+
+#[derive(Component, Default, Debug, Reflect)]
+#[reflect(Component)]
+pub struct ThreatTable(pub HashMap<Entity,f32>);
+
+impl Default for ThreatSettings {
+    fn default() -> Self {
+        Self {
+            damage_multiplier: 1.0,
+            healing_multiplier: 0.5,
+            proximity_radius: 6.0,
+            proximity_per_second: 2.0,
+            decay_per_second: 1.0
+        }
+    }
+}
+
+impl Plugin for ThreatPlugin {
+    fn build(&self, app: &mut App) {
+        app.register_type::<ThreatSettings>();
+        app.register_type::<ThreatTable>();
+        app.init_resource::<ThreatSettings>();
+        app.add_systems(FixedUpdate,(
+            attach_threat_tables,
+            raise_threat_from_damage,
+            raise_threat_from_healing,
+            raise_threat_from_proximity,
+            decay_threat,
+            clear_invalid_threat
+        ).chain().before(select_npc_targets));
+    }
+}
+
+impl ThreatTable {
+    pub fn add(&mut self, entity: Entity, amount: f32){
+        *self.0.entry(entity).or_insert(0.0) += amount;
+    }
+
+    pub fn decay(&mut self, amount: f32){
+        self.0.retain(|_, threat| {
+            *threat -= amount;
+            *threat > 0.0
+        });
+    }
+
+    pub fn highest(&self) -> Option<Entity>{
+        self.0.iter()
+            .max_by(|(_, a), (_, b)| a.total_cmp(b))
+            .map(|(entity, _)| *entity)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn decay_lowers_threat_and_drops_exhausted_entries() {
+        let mut threat_table = ThreatTable::default();
+        let tank = Entity::from_raw(1);
+        let passerby = Entity::from_raw(2);
+
+        threat_table.add(tank, 10.0);
+        threat_table.add(passerby, 1.0);
+        threat_table.decay(1.0);
+
+        assert_eq!(threat_table.0.get(&tank), Some(&9.0));
+        assert!(!threat_table.0.contains_key(&passerby));
+    }
+
+    #[test]
+    fn decay_keeps_highest_threat_target() {
+        let mut threat_table = ThreatTable::default();
+        let tank = Entity::from_raw(1);
+        let healer = Entity::from_raw(2);
+
+        threat_table.add(tank, 8.0);
+        threat_table.add(healer, 5.0);
+        threat_table.decay(3.0);
+
+        assert_eq!(threat_table.highest(), Some(tank));
+
+        threat_table.decay(5.0);
+
+        assert_eq!(threat_table.highest(), None);
+    }
+}
@@ -0,0 +1,232 @@
+use avian3d::prelude::Position;
+use bevy::asset::Assets;
+use bevy::math::{Vec2, Vec3};
+use bevy::prelude::{Entity, Query, Res, With};
+use leafwing_input_manager::action_state::ActionState;
+use lightyear::prelude::{Tick, TickManager};
+use shared::plugins::combatant::CombatantType;
+use shared::plugins::health::Health;
+use shared::plugins::statesmachine::{CurrentStates, States};
+use shared::protocol::CharacterAction;
+use crate::plugins::ai::{AiAction, AiCondition, BehaviourNode, BehaviourTree, NpcBrain, NpcOutput};
+use crate::plugins::threat::ThreatTable;
+
+#[derive(PartialEq)]
+enum BehaviourStatus{
+    Success,
+    Failure
+}
+
+struct BehaviourContext{
+    position: Vec3,
+    target_position: Option<Vec3>,
+    health_fraction: f32
+}
+
+fn is_alive(current_states: &CurrentStates) -> bool{
+    !current_states.0.contains_key(&States::Died)
+}
+
+pub fn select_npc_targets(
+    mut npc_query: Query<(Entity, &mut NpcBrain, &Position, Option<&ThreatTable>)>,
+    combatant_query: Query<(Entity, &Position, &CombatantType, &CurrentStates)>,
+){
+    for (npc_entity, mut npc_brain, npc_position, threat_table) in npc_query.iter_mut() {
+        if let Some(threat_target) = threat_table.and_then(|threat_table| threat_table.highest()) {
+            npc_brain.target = Some(threat_target);
+            continue;
+        }
+
+        let lose_range = npc_brain.sight_range * 1.5;
+
+        if let Some(target) = npc_brain.target {
+            let keep_target = combatant_query.get(target).is_ok_and(|(_, target_position, _, current_states)| {
+                is_alive(current_states) && target_position.0.distance(npc_position.0) <= lose_range
+            });
+
+            if !keep_target {
+                npc_brain.target = None;
+            }
+        }
+
+        if npc_brain.target.is_some() {
+            continue;
+        }
+
+        npc_brain.target = combatant_query.iter()
+            .filter(|(entity, _, combatant_type, current_states)| {
+                *entity != npc_entity && **combatant_type == CombatantType::Player && is_alive(current_states)
+            })
+            .map(|(entity, position, _, _)| (entity, position.0.distance(npc_position.0)))
+            .filter(|(_, distance)| *distance <= npc_brain.sight_range)
+            .min_by(|(_, a), (_, b)| a.total_cmp(b))
+            .map(|(entity, _)| entity);
+    }
+}
+
+pub fn run_npc_behaviour_trees(
+    mut npc_query: Query<(&mut NpcBrain, &mut NpcOutput, &Position, Option<&Health>)>,
+    target_query: Query<&Position, With<CombatantType>>,
+    behaviour_trees: Res<Assets<BehaviourTree>>,
+){
+    for (mut npc_brain, mut npc_output, position, health) in npc_query.iter_mut() {
+        *npc_output = NpcOutput::default();
+
+        let Some(behaviour_tree) = behaviour_trees.get(&npc_brain.tree) else {continue};
+        let context = BehaviourContext{
+            position: position.0,
+            target_position: npc_brain.target.and_then(|target| target_query.get(target).ok()).map(|target_position| target_position.0),
+            health_fraction: health.map(|health| health.current / health.max.max(f32::EPSILON)).unwrap_or(1.0)
+        };
+
+        npc_brain.active_action = None;
+        evaluate_node(&behaviour_tree.root, &context, &mut npc_brain, &mut npc_output);
+    }
+}
+
+fn evaluate_node(
+    node: &BehaviourNode,
+    context: &BehaviourContext,
+    npc_brain: &mut NpcBrain,
+    npc_output: &mut NpcOutput
+) -> BehaviourStatus{
+    match node {
+        BehaviourNode::Selector(children) => {
+            for child in children {
+                if evaluate_node(child, context, npc_brain, npc_output) == BehaviourStatus::Success {
+                    return BehaviourStatus::Success;
+                }
+            }
+
+            BehaviourStatus::Failure
+        },
+        BehaviourNode::Sequence(children) => {
+            let mut sequence_output = npc_output.clone();
+            let active_action = npc_brain.active_action.clone();
+
+            for child in children {
+                if evaluate_node(child, context, npc_brain, &mut sequence_output) == BehaviourStatus::Failure {
+                    npc_brain.active_action = active_action;
+                    return BehaviourStatus::Failure;
+                }
+            }
+
+            *npc_output = sequence_output;
+            BehaviourStatus::Success
+        },
+        BehaviourNode::Condition(condition) => {
+            if evaluate_condition(condition, context) {BehaviourStatus::Success} else {BehaviourStatus::Failure}
+        },
+        BehaviourNode::Action(action) => {
+            let status = run_action(action, context, npc_brain, npc_output);
+
+            if status == BehaviourStatus::Success {
+                npc_brain.active_action = Some(action.clone());
+            }
+
+            status
+        }
+    }
+}
+
+fn evaluate_condition(condition: &AiCondition, context: &BehaviourContext) -> bool{
+    match condition {
+        AiCondition::HasTarget => context.target_position.is_some(),
+        AiCondition::TargetWithin(distance) => context.target_position.is_some_and(|target| target.distance(context.position) <= *distance),
+        AiCondition::TargetBeyond(distance) => context.target_position.is_some_and(|target| target.distance(context.position) > *distance),
+        AiCondition::HealthBelow(fraction) => context.health_fraction < *fraction
+    }
+}
+
+fn run_action(
+    action: &AiAction,
+    context: &BehaviourContext,
+    npc_brain: &mut NpcBrain,
+    npc_output: &mut NpcOutput
+) -> BehaviourStatus{
+    let to_target = context.target_position.map(|target| (target - context.position).with_y(0.0));
+
+    match action {
+        AiAction::Idle => {},
+        AiAction::Wander(radius) => {
+            let home = *npc_brain.home.get_or_insert(context.position);
+            let reached_goal = npc_brain.wander_goal.is_none_or(|goal| goal.with_y(0.0).distance(context.position.with_y(0.0)) < 0.5);
+
+            if reached_goal {
+                let angle = npc_brain.next_random() * std::f32::consts::TAU;
+                let distance = npc_brain.next_random() * radius;
+
+                npc_brain.wander_goal = Some(home + Vec3::new(angle.cos() * distance, 0.0, angle.sin() * distance));
+            }
+
+            if let Some(goal) = npc_brain.wander_goal {
+                npc_output.move_direction = (goal - context.position).with_y(0.0).normalize_or_zero() * 0.5;
+                npc_output.move_goal = Some(goal);
+            }
+        },
+        AiAction::Chase => {
+            let Some(to_target) = to_target else {return BehaviourStatus::Failure};
+
+            npc_output.move_direction = to_target.normalize_or_zero();
+            npc_output.move_goal = context.target_position;
+        },
+        AiAction::KeepDistance(distance) => {
+            let Some(to_target) = to_target else {return BehaviourStatus::Failure};
+            let current_distance = to_target.length();
+
+            if current_distance < *distance {
+                npc_output.move_direction = -to_target.normalize_or_zero();
+            }else if current_distance > *distance + 1.0 {
+                npc_output.move_direction = to_target.normalize_or_zero();
+            }
+        },
+        AiAction::Attack => {
+            if to_target.is_none() {
+                return BehaviourStatus::Failure;
+            }
+
+            npc_output.attack = true;
+        },
+        AiAction::Block => {
+            if to_target.is_none() {
+                return BehaviourStatus::Failure;
+            }
+
+            npc_output.block = true;
+        },
+        AiAction::Flee => {
+            let Some(to_target) = to_target else {return BehaviourStatus::Failure};
+
+            npc_output.move_direction = -to_target.normalize_or_zero();
+        }
+    }
+
+    BehaviourStatus::Success
+}
+
+pub fn apply_npc_outputs(
+    mut npc_query: Query<(&mut NpcBrain, &NpcOutput, &mut ActionState<CharacterAction>)>,
+    tick_manager: Res<TickManager>,
+){
+    let tick = tick_manager.tick();
+
+    for (mut npc_brain, npc_output, mut action_state) in npc_query.iter_mut() {
+        let move_direction = npc_output.move_direction;
+        let cooling_down = npc_brain.last_attack_tick.is_some_and(|last_tick| (tick - Tick(last_tick)) < npc_brain.attack_cooldown_ticks as i16);
+
+        action_state.set_axis_pair(&CharacterAction::Move, Vec2::new(-move_direction.x, move_direction.z).clamp_length_max(1.0));
+
+        if npc_output.attack && !cooling_down && !action_state.pressed(&CharacterAction::Attack) {
+            action_state.press(&CharacterAction::Attack);
+            npc_brain.last_attack_tick = Some(tick.0);
+        }else {
+            action_state.release(&CharacterAction::Attack);
+        }
+
+        if npc_output.block {
+            action_state.press(&CharacterAction::Block);
+        }else {
+            action_state.release(&CharacterAction::Block);
+        }
+    }
+}
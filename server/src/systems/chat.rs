@@ -0,0 +1,117 @@
+use avian3d::prelude::Position;
+use bevy::prelude::{EventReader, Query, Res, ResMut, Time, With};
+use lightyear::prelude::ClientId;
+use lightyear::prelude::server::{DisconnectEvent, ServerReceiveMessage};
+use shared::plugins::combatant::{CombatantMarker, Team};
+use shared::protocol::{ChatChannel, ChatMessage, ChatMuteConfirmedMessage, ChatMuteMessage, ChatRejectedMessage, ChatRejection, ChatRequestMessage, ChatTarget, ServerMessages};
+use crate::plugins::chat::{ChatSettings, ChatState};
+
+pub fn receive_chat_requests(
+    mut chat_requests: EventReader<ServerReceiveMessage<ChatRequestMessage>>,
+    mut messages: ServerMessages,
+    mut chat_state: ResMut<ChatState>,
+    combatant_query: Query<(&Position, Option<&Team>), With<CombatantMarker>>,
+    settings: Res<ChatSettings>,
+    time: Res<Time>,
+){
+    for event in chat_requests.read() {
+        let sender = event.from();
+        let request = event.message();
+        let text = request.text.trim();
+
+        let rejection = if text.is_empty() {
+            Some(ChatRejection::Empty)
+        } else if text.chars().count() > settings.max_length {
+            Some(ChatRejection::TooLong)
+        } else if settings.muted_clients.contains(&sender.to_bits()) {
+            Some(ChatRejection::Muted)
+        } else if !chat_state.allow(sender, time.elapsed_secs(), &settings) {
+            Some(ChatRejection::RateLimited)
+        } else {
+            None
+        };
+
+        if let Some(reason) = rejection {
+            messages.send_to_client::<ChatChannel, ChatRejectedMessage>(sender, &mut ChatRejectedMessage{reason});
+            continue;
+        }
+
+        let sender_combatant = messages.client_combatant(sender).and_then(|entity| combatant_query.get(entity).ok());
+
+        let mut recipients: Vec<ClientId> = match request.target {
+            ChatTarget::All => messages.clients(),
+            ChatTarget::Team => {
+                let Some(team) = sender_combatant.and_then(|(_, team)| team.copied()) else {
+                    messages.send_to_client::<ChatChannel, ChatRejectedMessage>(sender, &mut ChatRejectedMessage{reason: ChatRejection::NoTeam});
+                    continue;
+                };
+
+                messages.team_clients(team)
+            },
+            ChatTarget::Whisper(recipient) => {
+                if !messages.clients().contains(&recipient) {
+                    messages.send_to_client::<ChatChannel, ChatRejectedMessage>(sender, &mut ChatRejectedMessage{reason: ChatRejection::UnknownRecipient});
+                    continue;
+                }
+
+                vec![recipient, sender]
+            },
+            ChatTarget::Proximity => {
+                let Some((sender_position, _)) = sender_combatant else {
+                    messages.send_to_client::<ChatChannel, ChatRejectedMessage>(sender, &mut ChatRejectedMessage{reason: ChatRejection::NoCombatant});
+                    continue;
+                };
+
+                messages.clients().into_iter()
+                    .filter(|client_id| {
+                        messages.client_combatant(*client_id)
+                            .and_then(|entity| combatant_query.get(entity).ok())
+                            .is_some_and(|(position, _)| position.0.distance(sender_position.0) <= settings.proximity_radius)
+                    })
+                    .collect()
+            }
+        };
+
+        recipients.sort_unstable_by_key(|client_id| client_id.to_bits());
+        recipients.dedup();
+        recipients.retain(|recipient| !chat_state.is_muted_by(*recipient, sender));
+
+        messages.send_to_clients::<ChatChannel, ChatMessage>(recipients, &mut ChatMessage{
+            sender,
+            target: request.target,
+            text: settings.filter(text)
+        });
+    }
+}
+
+pub fn receive_chat_mutes(
+    mut chat_mutes: EventReader<ServerReceiveMessage<ChatMuteMessage>>,
+    mut messages: ServerMessages,
+    mut chat_state: ResMut<ChatState>,
+){
+    for event in chat_mutes.read() {
+        let message = event.message();
+        let mutes = chat_state.mutes.entry(event.from()).or_default();
+
+        if message.muted {
+            mutes.insert(message.client_id);
+        }else {
+            mutes.remove(&message.client_id);
+        }
+
+        messages.send_to_client::<ChatChannel, ChatMuteConfirmedMessage>(event.from(), &mut ChatMuteConfirmedMessage{
+            client_id: message.client_id,
+            muted: message.muted
+        });
+    }
+}
+
+pub fn remove_disconnected_chat(
+    mut disconnections: EventReader<DisconnectEvent>,
+    mut chat_state: ResMut<ChatState>,
+){
+    for disconnection in disconnections.read() {
+        chat_state.recent_messages.remove(&disconnection.client_id);
+        chat_state.mutes.remove(&disconnection.client_id);
+    }
+}
@@ -0,0 +1,115 @@
+use std::path::Path;
+use bevy::diagnostic::DiagnosticsStore;
+use bevy::input::ButtonInput;
+use bevy::prelude::{Commands, Component, KeyCode, Node, PositionType, Query, Res, ResMut, Text, Time, Val, Visibility, With};
+use bevy::utils::default;
+use lightyear::prelude::IoDiagnosticsPlugin;
+use lightyear::prelude::server::ConnectionManager;
+use shared::plugins::diagnostics::{ChannelStats, NetworkDiagnosticsLog, NetworkDiagnosticsSettings, NetworkSample};
+use crate::plugins::diagnostics::ServerDiagnosticsCounters;
+
+#[derive(Component)]
+pub struct NetworkDiagnosticsPanel;
+
+pub fn spawn_server_diagnostics_panel(
+    mut commands: Commands,
+){
+    commands.spawn((
+        NetworkDiagnosticsPanel,
+        Text::new(""),
+        Node {
+            position_type: PositionType::Absolute,
+            top: Val::Px(8.0),
+            right: Val::Px(8.0),
+            ..default()
+        },
+        Visibility::Hidden,
+    ));
+}
+
+pub fn sample_server_diagnostics(
+    mut counters: ResMut<ServerDiagnosticsCounters>,
+    mut diagnostics_log: ResMut<NetworkDiagnosticsLog>,
+    mut channel_stats: ResMut<ChannelStats>,
+    mut text_query: Query<&mut Text, With<NetworkDiagnosticsPanel>>,
+    connection_manager: Res<ConnectionManager>,
+    diagnostics_store: Res<DiagnosticsStore>,
+    settings: Res<NetworkDiagnosticsSettings>,
+    time: Res<Time>,
+){
+    counters.timer += time.delta_secs();
+    diagnostics_log.elapsed += time.delta_secs();
+
+    if counters.timer < settings.sample_interval {
+        return;
+    }
+
+    let interval = counters.timer;
+    let diagnostic_value = |path| diagnostics_store.get(path).and_then(|diagnostic| diagnostic.smoothed()).unwrap_or(0.0) as f32;
+    let mut samples = vec![NetworkSample{
+        time: diagnostics_log.elapsed,
+        label: "server".to_string(),
+        bytes_in_per_second: diagnostic_value(&IoDiagnosticsPlugin::BYTES_IN),
+        bytes_out_per_second: diagnostic_value(&IoDiagnosticsPlugin::BYTES_OUT),
+        channels: channel_stats.sample(interval),
+        ..default()
+    }];
+    let mut client_ids: Vec<_> = connection_manager.connected_clients().collect();
+
+    client_ids.sort_by_key(|client_id| client_id.to_bits());
+
+    for client_id in client_ids {
+        let Ok(connection) = connection_manager.connection(client_id) else {continue};
+
+        samples.push(NetworkSample{
+            time: diagnostics_log.elapsed,
+            label: format!("client {client_id:?}"),
+            rtt_ms: connection.ping_manager.rtt().as_secs_f32() * 1000.0,
+            jitter_ms: connection.ping_manager.jitter().as_secs_f32() * 1000.0,
+            ..default()
+        });
+    }
+
+    if let Ok(mut text) = text_query.get_single_mut() {
+        text.0 = samples.iter().map(|sample| sample.summary()).collect::<Vec<_>>().join("\n");
+    }
+
+    for sample in samples {
+        diagnostics_log.push(sample, settings.max_samples);
+    }
+
+    *counters = ServerDiagnosticsCounters::default();
+}
+
+pub fn toggle_server_diagnostics(
+    keys: Res<ButtonInput<KeyCode>>,
+    mut settings: ResMut<NetworkDiagnosticsSettings>,
+    mut panel_query: Query<&mut Visibility, With<NetworkDiagnosticsPanel>>,
+){
+    if keys.just_pressed(KeyCode::F3) {
+        settings.visible = !settings.visible;
+    }
+
+    for mut visibility in panel_query.iter_mut() {
+        let target_visibility = if settings.visible {Visibility::Visible} else {Visibility::Hidden};
+
+        if *visibility != target_visibility {
+            *visibility = target_visibility;
+        }
+    }
+}
+
+pub fn export_server_diagnostics(
+    keys: Res<ButtonInput<KeyCode>>,
+    diagnostics_log: Res<NetworkDiagnosticsLog>,
+    settings: Res<NetworkDiagnosticsSettings>,
+){
+    if !keys.just_pressed(KeyCode::F4) {
+        return;
+    }
+
+    match diagnostics_log.write_csv(Path::new(&settings.export_path)) {
+        Ok(()) => log::info!("Exported {} network samples to {}", diagnostics_log.samples.len(), settings.export_path),
+        Err(error) => log::error!("Failed to export network diagnostics: {error}")
+    }
+}
@@ -0,0 +1,84 @@
+use avian3d::prelude::Position;
+use bevy::prelude::{Commands, Entity, EventReader, Query, Res, ResMut, Without};
+use lightyear::prelude::server::{DisconnectEvent, RoomManager, VisibilityMode};
+use shared::plugins::combatant::CombatantsList;
+use crate::plugins::interest::{room_id, ClientInterest, InterestCell, InterestSettings};
+
+pub fn attach_interest_cells(
+    mut commands: Commands,
+    replicated_query: Query<(Entity, &Position, &VisibilityMode), Without<InterestCell>>,
+    mut room_manager: ResMut<RoomManager>,
+    settings: Res<InterestSettings>,
+){
+    for (entity, position, visibility_mode) in replicated_query.iter() {
+        if *visibility_mode != VisibilityMode::InterestManagement {
+            continue;
+        }
+
+        let cell = settings.cell_of(position.0);
+
+        room_manager.add_entity(entity, room_id(cell));
+        commands.entity(entity).insert(InterestCell(cell));
+    }
+}
+
+pub fn update_interest_cells(
+    mut cell_query: Query<(Entity, &Position, &mut InterestCell)>,
+    mut room_manager: ResMut<RoomManager>,
+    settings: Res<InterestSettings>,
+){
+    for (entity, position, mut interest_cell) in cell_query.iter_mut() {
+        let Some(cell) = settings.next_cell(interest_cell.0, position.0) else {continue};
+
+        if cell == interest_cell.0 {
+            continue;
+        }
+
+        room_manager.remove_entity(entity, room_id(interest_cell.0));
+        room_manager.add_entity(entity, room_id(cell));
+        interest_cell.0 = cell;
+    }
+}
+
+pub fn update_client_interest(
+    combatants_list: Res<CombatantsList>,
+    cell_query: Query<&InterestCell>,
+    mut client_interest: ResMut<ClientInterest>,
+    mut room_manager: ResMut<RoomManager>,
+    settings: Res<InterestSettings>,
+){
+    for (entity, owner) in combatants_list.0.iter() {
+        let Some(client_id) = owner else {continue};
+        let Ok(interest_cell) = cell_query.get(*entity) else {continue};
+        let rooms = settings.rooms_around(interest_cell.0);
+        let previous_rooms = client_interest.0.entry(*client_id).or_default();
+
+        if *previous_rooms == rooms {
+            continue;
+        }
+
+        for room in previous_rooms.difference(&rooms) {
+            room_manager.remove_client(*client_id, *room);
+        }
+
+        for room in rooms.difference(previous_rooms) {
+            room_manager.add_client(*client_id, *room);
+        }
+
+        *previous_rooms = rooms;
+    }
+}
+
+pub fn remove_disconnected_interest(
+    mut disconnections: EventReader<DisconnectEvent>,
+    mut client_interest: ResMut<ClientInterest>,
+    mut room_manager: ResMut<RoomManager>,
+){
+    for disconnection in disconnections.read() {
+        let Some(rooms) = client_interest.0.remove(&disconnection.client_id) else {continue};
+
+        for room in rooms {
+            room_manager.remove_client(disconnection.client_id, room);
+        }
+    }
+}
@@ -0,0 +1,6 @@
+pub mod behaviourtree;
+pub mod navigation;
+pub mod threat;
+pub mod interest;
+pub mod diagnostics;
+pub mod chat;
\ No newline at end of file
@@ -0,0 +1,202 @@
+use avian3d::prelude::{Collider, Position, RigidBody, Rotation, SpatialQuery, SpatialQueryFilter};
+use bevy::color::palettes::css::{RED, YELLOW};
+use bevy::input::ButtonInput;
+use bevy::math::{Dir3, IVec2, Isometry3d, Quat, Vec3};
+use bevy::prelude::{Added, Changed, Commands, Entity, Gizmos, KeyCode, Or, Query, RemovedComponents, Res, ResMut, Time, With, Without};
+use shared::GameMask;
+use shared::plugins::combatant::{CombatantMarker, NpcCombatant};
+use crate::plugins::ai::NpcOutput;
+use crate::plugins::navigation::{NavAgent, NavMesh, NavMeshDebug, NavMeshSettings};
+
+const REBUILD_DELAY_FRAMES: u8 = 2;
+const REPATH_INTERVAL: f32 = 0.5;
+const REPATH_GOAL_DISTANCE: f32 = 1.0;
+const MAX_REPATH_BACKOFF: f32 = 8.0;
+
+pub fn mark_navmesh_dirty(
+    mut navmesh: ResMut<NavMesh>,
+    changed_query: Query<&RigidBody, (With<Collider>, Or<(Added<Collider>, Changed<Collider>, Changed<Position>, Changed<Rotation>)>)>,
+    mut removed_colliders: RemovedComponents<Collider>,
+    settings: Res<NavMeshSettings>,
+){
+    let static_changed = changed_query.iter().any(|rigid_body| rigid_body.is_static());
+    let collider_removed = removed_colliders.read().count() > 0;
+
+    if (static_changed || collider_removed || settings.is_changed()) && navmesh.rebuild_in_frames.is_none() {
+        navmesh.rebuild_in_frames = Some(REBUILD_DELAY_FRAMES);
+    }
+}
+
+pub fn build_navmesh(
+    mut navmesh: ResMut<NavMesh>,
+    mut spatial_query: SpatialQuery,
+    settings: Res<NavMeshSettings>,
+){
+    match navmesh.rebuild_in_frames {
+        Some(0) => {},
+        Some(frames) => {
+            navmesh.rebuild_in_frames = Some(frames - 1);
+            return;
+        },
+        None => return
+    }
+
+    spatial_query.update_pipeline();
+
+    let cells_per_side = ((settings.half_extent * 2.0) / settings.cell_size).ceil() as i32;
+    let origin = Vec3::new(-settings.half_extent, 0.0, -settings.half_extent);
+    let filter = SpatialQueryFilter::from_mask(GameMask::environment());
+    let clearance_height = (settings.agent_height - settings.max_step_height).max(0.1);
+    let clearance_collider = Collider::cylinder(settings.agent_radius, clearance_height);
+    let mut cells = Vec::with_capacity((cells_per_side * cells_per_side) as usize);
+
+    for z in 0..cells_per_side {
+        for x in 0..cells_per_side {
+            let cell_center = origin + Vec3::new((x as f32 + 0.5) * settings.cell_size, 0.0, (z as f32 + 0.5) * settings.cell_size);
+            let ray_origin = cell_center.with_y(settings.max_height);
+
+            let ground = spatial_query
+                .cast_ray(ray_origin, Dir3::NEG_Y, settings.max_height * 2.0, true, &filter)
+                .map(|ray_hit| settings.max_height - ray_hit.distance);
+
+            cells.push(ground.filter(|ground_height| {
+                let clearance_center = cell_center.with_y(ground_height + settings.max_step_height + clearance_height / 2.0);
+
+                spatial_query.shape_intersections(&clearance_collider, clearance_center, Quat::IDENTITY, &filter).is_empty()
+            }));
+        }
+    }
+
+    *navmesh = NavMesh{
+        origin,
+        cell_size: settings.cell_size,
+        width: cells_per_side,
+        depth: cells_per_side,
+        cells,
+        max_step_height: settings.max_step_height,
+        rebuild_in_frames: None
+    };
+}
+
+pub fn attach_nav_agents(
+    mut commands: Commands,
+    npc_query: Query<Entity, (With<NpcCombatant>, Without<NavAgent>)>,
+){
+    for entity in npc_query.iter() {
+        commands.entity(entity).insert(NavAgent::default());
+    }
+}
+
+pub fn steer_nav_agents(
+    mut agent_query: Query<(Entity, &mut NavAgent, &mut NpcOutput, &Position)>,
+    combatant_query: Query<(Entity, &Position), With<CombatantMarker>>,
+    navmesh: Res<NavMesh>,
+    settings: Res<NavMeshSettings>,
+    time: Res<Time>,
+){
+    for (entity, mut nav_agent, mut npc_output, position) in agent_query.iter_mut() {
+        let speed = npc_output.move_direction.length();
+
+        if let Some(goal) = npc_output.move_goal {
+            nav_agent.repath_timer -= time.delta_secs();
+
+            let goal_moved = nav_agent.goal.is_none_or(|previous_goal| previous_goal.distance(goal) > REPATH_GOAL_DISTANCE);
+            let backing_off = nav_agent.failed_attempts > 0 && nav_agent.repath_timer > 0.0;
+
+            if !backing_off && (goal_moved || nav_agent.repath_timer <= 0.0 || nav_agent.path.is_empty()) {
+                match navmesh.find_path(position.0, goal) {
+                    Some(path) => {
+                        nav_agent.path = path;
+                        nav_agent.failed_attempts = 0;
+                        nav_agent.repath_timer = REPATH_INTERVAL;
+                    },
+                    None => {
+                        nav_agent.path.clear();
+                        nav_agent.failed_attempts = nav_agent.failed_attempts.saturating_add(1);
+                        nav_agent.repath_timer = (REPATH_INTERVAL * 2f32.powi(nav_agent.failed_attempts as i32)).min(MAX_REPATH_BACKOFF);
+                    }
+                }
+
+                nav_agent.goal = Some(goal);
+            }
+
+            while nav_agent.path.len() > 1 && nav_agent.path[0].with_y(0.0).distance(position.0.with_y(0.0)) < navmesh.cell_size {
+                nav_agent.path.remove(0);
+            }
+
+            if let Some(waypoint) = nav_agent.path.first() {
+                npc_output.move_direction = (*waypoint - position.0).with_y(0.0).normalize_or_zero() * speed;
+            }
+        }else {
+            nav_agent.goal = None;
+            nav_agent.path.clear();
+            nav_agent.failed_attempts = 0;
+        }
+
+        if npc_output.move_direction == Vec3::ZERO {
+            continue;
+        }
+
+        let mut separation = Vec3::ZERO;
+
+        for (other_entity, other_position) in combatant_query.iter() {
+            if other_entity == entity {
+                continue;
+            }
+
+            let away = (position.0 - other_position.0).with_y(0.0);
+            let distance = away.length();
+
+            if distance > 0.0 && distance < settings.avoidance_radius {
+                separation += away / distance * (1.0 - distance / settings.avoidance_radius);
+            }
+        }
+
+        npc_output.move_direction = (npc_output.move_direction + separation * settings.avoidance_strength).clamp_length_max(1.0);
+    }
+}
+
+pub fn toggle_navmesh_debug(
+    keys: Res<ButtonInput<KeyCode>>,
+    mut navmesh_debug: ResMut<NavMeshDebug>,
+){
+    if keys.just_pressed(KeyCode::F2) {
+        navmesh_debug.0 = !navmesh_debug.0;
+    }
+}
+
+pub fn draw_navmesh_debug(
+    mut gizmos: Gizmos,
+    navmesh: Res<NavMesh>,
+    navmesh_debug: Res<NavMeshDebug>,
+    agent_query: Query<(&NavAgent, &Position)>,
+){
+    if !navmesh_debug.0 {
+        return;
+    }
+
+    let half_cell = navmesh.cell_size * 0.4;
+
+    for z in 0..navmesh.depth {
+        for x in 0..navmesh.width {
+            let cell = IVec2::new(x, z);
+
+            if navmesh.is_walkable(cell) {
+                continue;
+            }
+
+            let center = navmesh.origin + Vec3::new((x as f32 + 0.5) * navmesh.cell_size, 0.05, (z as f32 + 0.5) * navmesh.cell_size);
+
+            gizmos.line(center + Vec3::new(-half_cell, 0.0, -half_cell), center + Vec3::new(half_cell, 0.0, half_cell), RED);
+            gizmos.line(center + Vec3::new(-half_cell, 0.0, half_cell), center + Vec3::new(half_cell, 0.0, -half_cell), RED);
+        }
+    }
+
+    for (nav_agent, position) in agent_query.iter() {
+        gizmos.linestrip(std::iter::once(position.0).chain(nav_agent.path.iter().copied()), YELLOW);
+
+        for waypoint in nav_agent.path.iter() {
+            gizmos.sphere(Isometry3d::from_translation(*waypoint), 0.1, YELLOW);
+        }
+    }
+}
@@ -0,0 +1,91 @@
+use avian3d::prelude::Position;
+use bevy::prelude::{Commands, Entity, EventReader, Query, Res, Time, With, Without};
+use shared::plugins::combatant::{CombatantType, CombatantsList, NpcCombatant};
+use shared::plugins::health::{HealEvent, HitResolvedEvent};
+use shared::plugins::statesmachine::{CurrentStates, States};
+use crate::plugins::threat::{ThreatSettings, ThreatTable};
+
+pub fn attach_threat_tables(
+    mut commands: Commands,
+    npc_query: Query<Entity, (With<NpcCombatant>, Without<ThreatTable>)>,
+){
+    for entity in npc_query.iter() {
+        commands.entity(entity).insert(ThreatTable::default());
+    }
+}
+
+pub fn raise_threat_from_damage(
+    mut hit_resolved_events: EventReader<HitResolvedEvent>,
+    mut threat_query: Query<&mut ThreatTable>,
+    threat_settings: Res<ThreatSettings>,
+){
+    for event in hit_resolved_events.read() {
+        let Some(attacker) = event.attacker.filter(|_| event.amount > 0.0) else {continue};
+        let Ok(mut threat_table) = threat_query.get_mut(event.target) else {continue};
+
+        threat_table.add(attacker, event.amount * threat_settings.damage_multiplier);
+    }
+}
+
+pub fn raise_threat_from_healing(
+    mut heal_events: EventReader<HealEvent>,
+    mut threat_query: Query<&mut ThreatTable>,
+    threat_settings: Res<ThreatSettings>,
+){
+    for event in heal_events.read() {
+        let Some(healer) = event.healer else {continue};
+
+        for mut threat_table in threat_query.iter_mut() {
+            if threat_table.0.contains_key(&event.target) {
+                threat_table.add(healer, event.amount * threat_settings.healing_multiplier);
+            }
+        }
+    }
+}
+
+pub fn raise_threat_from_proximity(
+    mut threat_query: Query<(&mut ThreatTable, &Position)>,
+    player_query: Query<(Entity, &Position, &CombatantType)>,
+    threat_settings: Res<ThreatSettings>,
+    time: Res<Time>,
+){
+    let proximity_threat = threat_settings.proximity_per_second * time.delta_secs();
+
+    for (mut threat_table, npc_position) in threat_query.iter_mut() {
+        for (player_entity, player_position, combatant_type) in player_query.iter() {
+            if *combatant_type != CombatantType::Player {
+                continue;
+            }
+
+            let distance = player_position.0.distance(npc_position.0);
+
+            if distance < threat_settings.proximity_radius {
+                threat_table.add(player_entity, proximity_threat * (1.0 - distance / threat_settings.proximity_radius));
+            }
+        }
+    }
+}
+
+pub fn decay_threat(
+    mut threat_query: Query<&mut ThreatTable>,
+    threat_settings: Res<ThreatSettings>,
+    time: Res<Time>,
+){
+    let decay = threat_settings.decay_per_second * time.delta_secs();
+
+    for mut threat_table in threat_query.iter_mut() {
+        threat_table.decay(decay);
+    }
+}
+
+pub fn clear_invalid_threat(
+    mut threat_query: Query<&mut ThreatTable>,
+    states_query: Query<&CurrentStates>,
+    combatants_list: Res<CombatantsList>,
+){
+    for mut threat_table in threat_query.iter_mut() {
+        threat_table.0.retain(|entity, _| {
+            combatants_list.0.contains_key(entity) && states_query.get(*entity).is_ok_and(|current_states| !current_states.0.contains_key(&States::Died))
+        });
+    }
+}
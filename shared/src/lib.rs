@@ -1,4 +1,4 @@
-use avian3d::prelude::PhysicsLayer;
+use avian3d::prelude::{CollisionLayers, LayerMask, PhysicsLayer};
 use bevy::prelude::Component;
 
 pub mod protocol;
@@ -10,7 +10,23 @@ pub enum GameMask{
     #[default]
     Default,
     Floor,
-    Combatant
+    Prop,
+    Combatant,
+    Projectile
+}
+
+impl GameMask {
+    pub fn environment() -> LayerMask{
+        LayerMask::from([GameMask::Default, GameMask::Floor, GameMask::Prop])
+    }
+
+    pub fn floor_layers() -> CollisionLayers{
+        CollisionLayers::new(GameMask::Floor, LayerMask::ALL)
+    }
+
+    pub fn combatant_layers() -> CollisionLayers{
+        CollisionLayers::new(GameMask::Combatant, [GameMask::Default, GameMask::Floor, GameMask::Prop, GameMask::Projectile])
+    }
 }
 #[derive(Component)]
 pub struct InteractNetworkAble;
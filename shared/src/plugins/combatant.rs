@@ -1,21 +1,25 @@
 use std::cmp::PartialEq;
-use avian3d::prelude::{Collider, Friction, GravityScale, LockedAxes, RigidBody, ShapeHitData};
+use avian3d::prelude::{Collider, CollisionLayers, Friction, GravityScale, LockedAxes, RigidBody, ShapeHitData};
 use bevy::app::App;
 use bevy::asset::AssetServer;
-use bevy::prelude::{Added, BuildChildren, Bundle, Commands, Component, Entity, EventReader, First, FixedUpdate, GltfAssetLabel, Has, InheritedVisibility, IntoSystemConfigs, KeyCode, Or, Plugin, Query, Res, ResMut, Resource, Transform, With, Without};
+use bevy::math::Vec3;
+use bevy::prelude::{Added, BuildChildren, Bundle, DespawnRecursiveExt, Commands, Component, Entity, Event, EventReader, First, FixedUpdate, GltfAssetLabel, Has, InheritedVisibility, IntoSystemConfigs, KeyCode, MouseButton, Or, Plugin, Query, Reflect, ReflectComponent, RemovedComponents, Res, ResMut, Resource, Transform, With, Without};
 use bevy::scene::SceneRoot;
 use bevy::utils::default;
 use bevy::utils::hashbrown::HashMap;
 use leafwing_input_manager::prelude::{ActionState, InputMap, VirtualDPad};
-use lightyear::connection::client::ClientConnection;
-use lightyear::prelude::{ClientId, Deserialize, NetworkTarget, Serialize};
-use lightyear::prelude::client::{Interpolated, NetClient, Predicted};
-use lightyear::prelude::server::{ConnectEvent, ControlledBy, Replicate, SyncTarget};
+use lightyear::prelude::{ClientId, Deserialize, NetworkTarget, ReplicationGroup, Serialize};
+use lightyear::prelude::client::{Interpolated, Predicted};
+use lightyear::prelude::server::{ConnectEvent, ControlledBy, DisconnectEvent, Replicate, SyncTarget, VisibilityMode};
 use lightyear::shared::replication::components::Controlled;
 use crate::{GameMask, InteractNetworkAble, NetworkSide};
+use crate::plugins::health::Health;
+use crate::plugins::spawnpoints::SpawnPointSelector;
 use crate::plugins::statesmachine::CurrentStates;
-use crate::protocol::{CharacterAction, REPLICATION_GROUP};
-use crate::systems::charactercontroller::{adjust_collider_float, character_walk, check_is_grounded, control_gravity};
+use crate::protocol::CharacterAction;
+use crate::systems::charactercontroller::{adjust_collider_float, character_crouch, character_dash, character_dash_input, character_jump, character_jump_input, character_slide, character_stagger, character_stamina, character_step_up, character_walk, check_is_grounded, control_gravity, separate_combatants};
+
+pub const PLAYER_TEAMS: u8 = 2;
 
 #[derive(Resource)]
 pub struct CombatantsList(pub HashMap<Entity,Option<ClientId>>);
@@ -35,18 +39,105 @@ pub enum CombatantType{
     Npc
 }
 
+#[derive(Component, Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq, Hash, Reflect)]
+pub struct Team(pub u8);
+
+#[derive(Component, Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub struct CombatantOwner(pub ClientId);
+
+#[derive(Component, Serialize, Deserialize, Clone, Debug, PartialEq, Reflect)]
+pub struct Stamina{
+    pub current: f32,
+    pub max: f32
+}
+
+#[derive(Component, Serialize, Deserialize, Clone, Debug, Default, PartialEq, Reflect)]
+pub struct JumpTimers{
+    pub last_grounded_tick: Option<u16>,
+    pub jump_pressed_tick: Option<u16>
+}
+
 #[derive(Component)]
 pub struct PlayerCombatant;
 
+#[derive(Component)]
+pub struct NpcCombatant;
+
+#[derive(Event, Clone, Debug)]
+pub struct DashStarted{
+    pub entity: Entity,
+    pub position: Vec3
+}
+
+#[derive(Event, Clone, Debug, Default)]
+pub struct SpawnNpcCombatant{
+    pub transform: Option<Transform>,
+    pub team: Option<Team>
+}
+
+#[derive(Resource, Clone, Debug)]
+pub struct DashSettings{
+    pub distance: f32,
+    pub duration_ticks: u16,
+    pub invulnerable_ticks: u16
+}
+
+#[derive(Resource, Clone, Debug)]
+pub struct CombatSettings{
+    pub melee_damage: f32,
+    pub melee_range: f32,
+    pub melee_radius: f32,
+    pub block_angle: f32,
+    pub block_damage_reduction: f32,
+    pub parry_ticks: u16,
+    pub stagger_ticks: u16,
+    pub knockback_strength: f32,
+    pub knockback_stun_ticks: u16
+}
+
 #[derive(Component)]
 pub struct CharacterController{
     pub shape_hit_data: Option<ShapeHitData>,
-    pub grounded: bool
+    pub ground_normal: Vec3,
+    pub ground_velocity: Vec3,
+    pub grounded: bool,
+    pub crouched: bool
+}
+
+#[derive(Component, Serialize, Deserialize, Clone, Debug, PartialEq, Reflect)]
+#[reflect(Component)]
+pub struct CharacterControllerSettings{
+    pub max_speed: f32,
+    pub acceleration: f32,
+    pub deceleration: f32,
+    pub turn_acceleration: f32,
+    pub air_control: f32,
+    pub float_height: f32,
+    pub foot_offset: Option<f32>,
+    pub float_dead_zone: f32,
+    pub spring_stiffness: f32,
+    pub spring_damping: f32,
+    pub jump_height: f32,
+    pub coyote_ticks: u16,
+    pub jump_buffer_ticks: u16,
+    pub slope_limit: f32,
+    pub max_step_height: f32,
+    pub combatants_as_ground: bool,
+    pub separation_strength: f32,
+    pub sprint_multiplier: f32,
+    pub crouch_multiplier: f32,
+    pub crouch_toggle: bool,
+    pub stand_height: f32,
+    pub crouch_height: f32,
+    pub stamina_drain: f32,
+    pub stamina_regen: f32
 }
 
 #[derive(Bundle)]
 pub struct CombatantServerBundle{
     character_controller: CharacterController,
+    character_controller_settings: CharacterControllerSettings,
+    jump_timers: JumpTimers,
     rigid_body: RigidBody,
     collider: Collider,
     gravity_scale: GravityScale,
@@ -55,10 +146,13 @@ pub struct CombatantServerBundle{
     combatant_marker: CombatantMarker,
     network_side: NetworkSide,
     current_states: CurrentStates,
+    health: Health,
+    stamina: Stamina,
     transform: Transform,
     replicate: Replicate,
     locked_axes: LockedAxes,
     game_mask: GameMask,
+    collision_layers: CollisionLayers,
     inherited_visibility: InheritedVisibility,
     interact_network_able: InteractNetworkAble,
     action_state: ActionState<CharacterAction>
@@ -69,10 +163,12 @@ pub struct CombatantClientBundle{
     character_controller: CharacterController,
     rigid_body: RigidBody,
     collider: Collider,
+    gravity_scale: GravityScale,
     friction: Friction,
     network_side: NetworkSide,
     locked_axes: LockedAxes,
     game_mask: GameMask,
+    collision_layers: CollisionLayers,
     inherited_visibility: InheritedVisibility,
     interact_network_able: InteractNetworkAble
 }
@@ -92,7 +188,76 @@ impl Default for CharacterController{
     fn default()->Self{
         Self{
             shape_hit_data: None,
-            grounded: true
+            ground_normal: Vec3::Y,
+            ground_velocity: Vec3::ZERO,
+            grounded: true,
+            crouched: false
+        }
+    }
+}
+
+impl Default for CharacterControllerSettings{
+    fn default()->Self{
+        Self{
+            max_speed: 4.0,
+            acceleration: 40.0,
+            deceleration: 30.0,
+            turn_acceleration: 60.0,
+            air_control: 0.3,
+            float_height: 0.1,
+            foot_offset: None,
+            float_dead_zone: 0.005,
+            spring_stiffness: 200.0,
+            spring_damping: 28.0,
+            jump_height: 1.2,
+            coyote_ticks: 6,
+            jump_buffer_ticks: 8,
+            slope_limit: 45.0,
+            max_step_height: 0.3,
+            combatants_as_ground: false,
+            separation_strength: 10.0,
+            sprint_multiplier: 1.6,
+            crouch_multiplier: 0.5,
+            crouch_toggle: false,
+            stand_height: 1.6,
+            crouch_height: 1.0,
+            stamina_drain: 20.0,
+            stamina_regen: 15.0
+        }
+    }
+}
+
+impl Default for Stamina{
+    fn default()->Self{
+        Self{
+            current: 100.0,
+            max: 100.0
+        }
+    }
+}
+
+impl Default for DashSettings{
+    fn default()->Self{
+        Self{
+            distance: 4.0,
+            duration_ticks: 12,
+            invulnerable_ticks: 8
+        }
+    }
+}
+
+impl Default for CombatSettings{
+    fn default()->Self{
+        Self{
+            melee_damage: 10.0,
+            melee_range: 1.0,
+            melee_radius: 0.6,
+            block_angle: 60.0,
+            block_damage_reduction: 0.8,
+            parry_ticks: 10,
+            stagger_ticks: 48,
+            knockback_strength: 5.0,
+            knockback_stun_ticks: 16
         }
     }
 }
@@ -111,6 +276,8 @@ impl Default for CombatantServerBundle{
     fn default() -> CombatantServerBundle{
         CombatantServerBundle{
             character_controller: CharacterController::default(),
+            character_controller_settings: CharacterControllerSettings::default(),
+            jump_timers: JumpTimers::default(),
             rigid_body: RigidBody::Dynamic,
             collider: Collider::capsule(0.3,1.0),
             gravity_scale: GravityScale(0.0),
@@ -119,10 +286,13 @@ impl Default for CombatantServerBundle{
             combatant_marker: CombatantMarker,
             network_side: NetworkSide::Server,
             current_states: CurrentStates::default(),
+            health: Health::default(),
+            stamina: Stamina::default(),
             transform: Transform::from_xyz(0.0, 0.85, 0.0),
             replicate: Replicate::default(),
             locked_axes: LockedAxes::new().lock_rotation_x().lock_rotation_z(),
             game_mask: GameMask::Combatant,
+            collision_layers: GameMask::combatant_layers(),
             inherited_visibility: InheritedVisibility::VISIBLE,
             interact_network_able: InteractNetworkAble,
             action_state: ActionState::<CharacterAction>::default()
@@ -136,10 +306,12 @@ impl Default for CombatantClientBundle{
             character_controller: CharacterController::default(),
             rigid_body: RigidBody::Dynamic,
             collider: Collider::capsule(0.3,1.0),
+            gravity_scale: GravityScale(0.0),
             friction: Friction::new(1.0),
             network_side: NetworkSide::Client,
             locked_axes: LockedAxes::new().lock_rotation_x().lock_rotation_z(),
             game_mask: GameMask::Combatant,
+            collision_layers: GameMask::combatant_layers(),
             inherited_visibility: InheritedVisibility::VISIBLE,
             interact_network_able: InteractNetworkAble
         }
@@ -148,25 +320,31 @@ impl Default for CombatantClientBundle{
 
 impl Plugin for CombatantPlugin {
     fn build(&self, app: &mut App) {
+        app.register_type::<CharacterControllerSettings>();
+        app.register_type::<Stamina>();
+        app.register_type::<JumpTimers>();
         app.insert_resource(CombatantsList(HashMap::new()));
+        app.init_resource::<DashSettings>();
+        app.init_resource::<CombatSettings>();
+        app.add_event::<DashStarted>();
         if self.network_side == NetworkSide::Client {
-            app.add_systems(First,client_combatant_added);
+            app.add_systems(First,(client_combatant_added,client_combatant_removed));
         }else {
-            app.add_systems(First,create_player_combatant);
+            app.add_event::<SpawnNpcCombatant>();
+            app.add_systems(First,(create_player_combatant,create_npc_combatant,remove_disconnected_combatants));
         }
 
-        app.add_systems(FixedUpdate,(check_is_grounded,adjust_collider_float,character_walk,control_gravity).chain());
+        app.add_systems(FixedUpdate,(check_is_grounded,character_jump_input,character_dash_input,character_crouch,adjust_collider_float,character_slide,character_step_up,character_walk,separate_combatants,character_stamina,control_gravity,character_jump,character_dash,character_stagger).chain());
     }
 }
 
 fn client_combatant_added(
     mut commands: Commands,
-    combatant_query: Query<(Entity,Has<Controlled>), (Or<(Added<Predicted>, Added<Interpolated>)>, With<CombatantMarker>, Without<NetworkSide>)>,
-    connection: Res<ClientConnection>,
+    combatant_query: Query<(Entity,Has<Controlled>,Option<&CombatantOwner>), (Or<(Added<Predicted>, Added<Interpolated>)>, With<CombatantMarker>, Without<NetworkSide>)>,
     asset_server: Res<AssetServer>,
     mut combatants_list: ResMut<CombatantsList>,
 ){
-    for (entity,is_controlled) in combatant_query.iter(){
+    for (entity,is_controlled,combatant_owner) in combatant_query.iter(){
         let mesh_entity = commands.spawn(
             CombatantMeshBundle{
                 scene_root: SceneRoot(
@@ -182,29 +360,62 @@ fn client_combatant_added(
         if is_controlled {
             commands.entity(entity).insert((
                 PlayerCombatant,
-                InputMap::new([(CharacterAction::Jump,KeyCode::Space)]).with_dual_axis(CharacterAction::Move, VirtualDPad::wasd()),
+                InputMap::new([(CharacterAction::Jump,KeyCode::Space),(CharacterAction::Dash,KeyCode::ShiftLeft),(CharacterAction::Sprint,KeyCode::AltLeft),(CharacterAction::Crouch,KeyCode::ControlLeft),(CharacterAction::Fire,KeyCode::KeyF)])
+                    .with_multiple([(CharacterAction::Block,MouseButton::Right),(CharacterAction::Attack,MouseButton::Left)])
+                    .with_dual_axis(CharacterAction::Move, VirtualDPad::wasd()),
             ));
         }
 
-        combatants_list.0.insert(entity,Some(connection.id()));
+        combatants_list.0.insert(entity,combatant_owner.map(|owner| owner.0));
+    }
+}
+
+fn client_combatant_removed(
+    mut removed_combatants: RemovedComponents<CombatantMarker>,
+    mut combatants_list: ResMut<CombatantsList>,
+){
+    for entity in removed_combatants.read() {
+        combatants_list.0.remove(&entity);
     }
 }
 
 pub fn create_player_combatant(
     mut connections: EventReader<ConnectEvent>,
     mut commands: Commands,
-    mut combatants_list: ResMut<CombatantsList>
+    mut combatants_list: ResMut<CombatantsList>,
+    mut spawn_point_selector: SpawnPointSelector,
+    team_query: Query<&Team, With<CombatantMarker>>,
 ){
+    let mut team_sizes: Vec<usize> = (0..PLAYER_TEAMS)
+        .map(|team| combatants_list.0.iter()
+            .filter(|(entity, client_id)| client_id.is_some() && team_query.get(**entity).is_ok_and(|combatant_team| combatant_team.0 == team))
+            .count())
+        .collect();
+
     for connection in connections.read() {
         let client_id = connection.client_id;
+        let team_index = (0..team_sizes.len()).min_by_key(|index| team_sizes[*index]).unwrap_or(0);
+        let team = Team(team_index as u8);
+        let combatant_bundle = CombatantServerBundle::default();
+        let transform = spawn_point_selector
+            .select(Some(&team), None, &combatant_bundle.collider)
+            .unwrap_or(combatant_bundle.transform);
+
+        spawn_point_selector.reserve(transform.translation, Some(team));
 
-        let entity = commands.spawn(CombatantServerBundle{
+        if let Some(team_size) = team_sizes.get_mut(team_index) {
+            *team_size += 1;
+        }
+
+        let entity = commands.spawn((CombatantServerBundle{
+            transform,
             replicate: Replicate {
                 controlled_by: ControlledBy {
                     target: NetworkTarget::Single(client_id),
                     ..default()
                 },
-                group: REPLICATION_GROUP,
+                group: ReplicationGroup::default(),
+                visibility: VisibilityMode::InterestManagement,
                 sync: SyncTarget {
                     prediction: NetworkTarget::Single(client_id),
                     interpolation: NetworkTarget::AllExceptSingle(client_id),
@@ -212,9 +423,69 @@ pub fn create_player_combatant(
                 },
                 ..default()
             },
-            ..default()
-        });
+            ..combatant_bundle
+        },team,CombatantOwner(client_id)));
 
         combatants_list.0.insert(entity.id(),Some(client_id));
     }
+}
+
+pub fn remove_disconnected_combatants(
+    mut commands: Commands,
+    mut disconnections: EventReader<DisconnectEvent>,
+    mut combatants_list: ResMut<CombatantsList>
+){
+    for disconnection in disconnections.read() {
+        combatants_list.0.retain(|entity, client_id| {
+            if *client_id != Some(disconnection.client_id) {
+                return true;
+            }
+
+            if let Some(entity_commands) = commands.get_entity(*entity) {
+                entity_commands.despawn_recursive();
+            }
+
+            false
+        });
+    }
+}
+
+pub fn create_npc_combatant(
+    mut spawn_npc_events: EventReader<SpawnNpcCombatant>,
+    mut commands: Commands,
+    mut combatants_list: ResMut<CombatantsList>,
+    mut spawn_point_selector: SpawnPointSelector,
+){
+    for event in spawn_npc_events.read() {
+        let combatant_bundle = CombatantServerBundle::default();
+        let transform = event.transform
+            .or_else(|| spawn_point_selector.select(event.team.as_ref(), None, &combatant_bundle.collider))
+            .unwrap_or(combatant_bundle.transform);
+
+        spawn_point_selector.reserve(transform.translation, event.team);
+
+        let mut entity = commands.spawn((
+            CombatantServerBundle{
+                transform,
+                combatant_type: CombatantType::Npc,
+                replicate: Replicate {
+                    group: ReplicationGroup::default(),
+                    visibility: VisibilityMode::InterestManagement,
+                    sync: SyncTarget {
+                        interpolation: NetworkTarget::All,
+                        ..default()
+                    },
+                    ..default()
+                },
+                ..combatant_bundle
+            },
+            NpcCombatant,
+        ));
+
+        if let Some(team) = event.team {
+            entity.insert(team);
+        }
+
+        combatants_list.0.insert(entity.id(),None);
+    }
 }
\ No newline at end of file
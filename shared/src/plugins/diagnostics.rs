@@ -0,0 +1,204 @@
+use std::fs::File;
+use std::io::{BufWriter, Write};
+use std::path::Path;
+use bevy::app::{App, Plugin};
+use bevy::prelude::{Reflect, ReflectResource, Resource};
+use bevy::utils::hashbrown::HashMap;
+use lightyear::prelude::Channel;
+
+pub struct NetworkDiagnosticsPlugin;
+
+#[derive(Resource, Clone, Debug, Reflect)]
+#[reflect(Resource)]
+pub struct NetworkDiagnosticsSettings{
+    pub visible: bool,
+    pub sample_interval: f32,
+    pub max_samples: usize,
+    pub export_path: String
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct NetworkSample{
+    pub time: f32,
+    pub label: String,
+    pub rtt_ms: f32,
+    pub jitter_ms: f32,
+    pub input_delay_ticks: u16,
+    pub bytes_in_per_second: f32,
+    pub bytes_out_per_second: f32,
+    pub rollbacks_per_second: f32,
+    pub average_rollback_depth: f32,
+    pub position_correction: f32,
+    pub rotation_correction: f32,
+    pub channels: HashMap<String,ChannelTraffic>
+}
+
+#[derive(Clone, Copy, Debug, Default)]
+pub struct ChannelTraffic{
+    pub messages_in: f32,
+    pub messages_out: f32,
+    pub estimated_bytes_in: f32,
+    pub estimated_bytes_out: f32
+}
+
+#[derive(Resource, Default)]
+pub struct ChannelStats(pub HashMap<String,ChannelTraffic>);
+
+#[derive(Resource, Default)]
+pub struct NetworkDiagnosticsLog{
+    pub samples: Vec<NetworkSample>,
+    pub elapsed: f32
+}
+
+impl Default for NetworkDiagnosticsSettings {
+    fn default() -> Self {
+        Self {
+            visible: false,
+            sample_interval: 1.0,
+            max_samples: 3600,
+            export_path: "network_diagnostics.csv".to_string()
+        }
+    }
+}
+
+impl Plugin for NetworkDiagnosticsPlugin {
+    fn build(&self, app: &mut App) {
+        app.register_type::<NetworkDiagnosticsSettings>();
+        app.init_resource::<NetworkDiagnosticsSettings>();
+        app.init_resource::<NetworkDiagnosticsLog>();
+    }
+}
+
+impl ChannelTraffic {
+    pub fn per_second(&self, interval: f32) -> Self{
+        Self{
+            messages_in: self.messages_in / interval,
+            messages_out: self.messages_out / interval,
+            estimated_bytes_in: self.estimated_bytes_in / interval,
+            estimated_bytes_out: self.estimated_bytes_out / interval
+        }
+    }
+}
+
+impl ChannelStats {
+    pub fn register<C: Channel>(&mut self){
+        self.0.entry(channel_name::<C>()).or_default();
+    }
+
+    pub fn record_in<C: Channel, M>(&mut self, message: &M){
+        let traffic = self.0.entry(channel_name::<C>()).or_default();
+
+        traffic.messages_in += 1.0;
+        traffic.estimated_bytes_in += std::mem::size_of_val(message) as f32;
+    }
+
+    pub fn record_out<C: Channel, M>(&mut self, message: &M, recipients: usize){
+        let traffic = self.0.entry(channel_name::<C>()).or_default();
+
+        traffic.messages_out += recipients as f32;
+        traffic.estimated_bytes_out += (std::mem::size_of_val(message) * recipients) as f32;
+    }
+
+    pub fn sample(&mut self, interval: f32) -> HashMap<String,ChannelTraffic>{
+        self.0.iter_mut()
+            .map(|(channel, traffic)| (channel.clone(), std::mem::take(traffic).per_second(interval)))
+            .collect()
+    }
+}
+
+impl NetworkSample {
+    pub fn summary(&self) -> String{
+        let mut channels: Vec<_> = self.channels.iter().collect();
+
+        channels.sort_by(|(a, _), (b, _)| a.cmp(b));
+
+        let channels = channels.iter()
+            .map(|(channel, traffic)| format!(
+                "  {channel}: in ~{:.0} B/s est. ({:.1} msg/s)  out ~{:.0} B/s est. ({:.1} msg/s)",
+                traffic.estimated_bytes_in,
+                traffic.messages_in,
+                traffic.estimated_bytes_out,
+                traffic.messages_out
+            ))
+            .collect::<Vec<_>>()
+            .join("\n");
+
+        format!(
+            "{}\nRTT: {:.1} ms  jitter: {:.1} ms\ninput delay: {} ticks\nin: {:.0} B/s  out: {:.0} B/s\nrollbacks: {:.1}/s  avg depth: {:.1}\ncorrection: pos {:.3}  rot {:.3}\n{}",
+            self.label,
+            self.rtt_ms,
+            self.jitter_ms,
+            self.input_delay_ticks,
+            self.bytes_in_per_second,
+            self.bytes_out_per_second,
+            self.rollbacks_per_second,
+            self.average_rollback_depth,
+            self.position_correction,
+            self.rotation_correction,
+            channels
+        )
+    }
+}
+
+impl NetworkDiagnosticsLog {
+    pub fn push(&mut self, sample: NetworkSample, max_samples: usize){
+        self.samples.push(sample);
+
+        if self.samples.len() > max_samples {
+            let overflow = self.samples.len() - max_samples;
+
+            self.samples.drain(..overflow);
+        }
+    }
+
+    pub fn write_csv(&self, path: &Path) -> std::io::Result<()>{
+        let mut channels: Vec<&String> = self.samples.iter()
+            .flat_map(|sample| sample.channels.keys())
+            .collect();
+
+        channels.sort();
+        channels.dedup();
+
+        let mut writer = BufWriter::new(File::create(path)?);
+
+        write!(writer, "time,label,rtt_ms,jitter_ms,input_delay_ticks,bytes_in_per_second,bytes_out_per_second,rollbacks_per_second,average_rollback_depth,position_correction,rotation_correction")?;
+
+        for channel in channels.iter() {
+            write!(writer, ",{channel}_estimated_bytes_in_per_second,{channel}_estimated_bytes_out_per_second,{channel}_messages_in_per_second,{channel}_messages_out_per_second")?;
+        }
+
+        writeln!(writer)?;
+
+        for sample in self.samples.iter() {
+            write!(
+                writer,
+                "{:.3},{},{:.3},{:.3},{},{:.1},{:.1},{:.3},{:.3},{:.5},{:.5}",
+                sample.time,
+                sample.label,
+                sample.rtt_ms,
+                sample.jitter_ms,
+                sample.input_delay_ticks,
+                sample.bytes_in_per_second,
+                sample.bytes_out_per_second,
+                sample.rollbacks_per_second,
+                sample.average_rollback_depth,
+                sample.position_correction,
+                sample.rotation_correction
+            )?;
+
+            for channel in channels.iter() {
+                let traffic = sample.channels.get(*channel).copied().unwrap_or_default();
+
+                write!(writer, ",{:.1},{:.1},{:.3},{:.3}", traffic.estimated_bytes_in, traffic.estimated_bytes_out, traffic.messages_in, traffic.messages_out)?;
+            }
+
+            writeln!(writer)?;
+        }
+
+        writer.flush()
+    }
+}
+
+pub fn channel_name<C: Channel>() -> String{
+    std::any::type_name::<C>().rsplit("::").next().unwrap_or_default().to_string()
+}
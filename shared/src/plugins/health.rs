@@ -0,0 +1,201 @@
+use avian3d::prelude::{Position, Rotation};
+use bevy::app::{App, FixedUpdate, Plugin};
+use bevy::math::Vec3;
+use bevy::prelude::{default, Component, Entity, Event, EventReader, EventWriter, Query, Reflect, Res, With};
+use lightyear::prelude::{Tick, TickManager};
+use serde::{Deserialize, Serialize};
+use crate::{InteractNetworkAble, NetworkSide};
+use crate::plugins::combatant::{CombatSettings, CombatantMarker, DashSettings};
+use crate::plugins::knockback::ApplyKnockback;
+use crate::plugins::statesmachine::{CurrentStates, StateInfos, States, StatesValues};
+use crate::protocol::{GameplayChannel, HitResultMessage, KillNoticeMessage, ServerMessages};
+use crate::systems::characteractions::is_invulnerable;
+
+pub struct HealthPlugin{
+    pub network_side: NetworkSide
+}
+
+#[derive(Component, Serialize, Deserialize, Clone, Debug, PartialEq, Reflect)]
+pub struct Health{
+    pub current: f32,
+    pub max: f32
+}
+
+#[derive(Event, Clone, Debug)]
+pub struct DamageEvent{
+    pub target: Entity,
+    pub attacker: Option<Entity>,
+    pub amount: f32
+}
+
+#[derive(Event, Clone, Debug)]
+pub struct HitResolvedEvent{
+    pub target: Entity,
+    pub attacker: Option<Entity>,
+    pub result: HitResult,
+    pub amount: f32
+}
+
+#[derive(Event, Clone, Debug)]
+pub struct HealEvent{
+    pub target: Entity,
+    pub healer: Option<Entity>,
+    pub amount: f32
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq, Reflect)]
+pub enum HitResult{
+    Hit,
+    Blocked,
+    Parried,
+    Ignored
+}
+
+impl Default for Health {
+    fn default() -> Self {
+        Self {
+            current: 100.0,
+            max: 100.0
+        }
+    }
+}
+
+impl Plugin for HealthPlugin {
+    fn build(&self, app: &mut App) {
+        app.register_type::<Health>();
+        app.add_event::<DamageEvent>();
+        app.add_event::<HitResolvedEvent>();
+        app.add_event::<HealEvent>();
+
+        if self.network_side == NetworkSide::Server {
+            app.add_systems(FixedUpdate,(apply_damage,apply_healing));
+        }
+    }
+}
+
+pub fn resolve_hit(
+    defender_states: &CurrentStates,
+    defender_position: &Position,
+    defender_rotation: &Rotation,
+    attacker_position: Option<&Position>,
+    tick: Tick,
+    combat_settings: &CombatSettings,
+    dash_settings: &DashSettings
+) -> HitResult{
+    if defender_states.0.contains_key(&States::Died) || is_invulnerable(defender_states, tick, dash_settings) {
+        return HitResult::Ignored;
+    }
+
+    let (Some(StatesValues::Blocking(block_start_tick)), Some(attacker_position)) = (
+        defender_states.0.get(&States::Blocking).and_then(|state_infos| state_infos.values.as_ref()),
+        attacker_position
+    ) else {
+        return HitResult::Hit;
+    };
+
+    let facing = (defender_rotation.0 * Vec3::Z).with_y(0.0).normalize_or_zero();
+    let to_attacker = (attacker_position.0 - defender_position.0).with_y(0.0).normalize_or_zero();
+
+    if facing.angle_between(to_attacker) > combat_settings.block_angle.to_radians() {
+        return HitResult::Hit;
+    }
+
+    if (tick - Tick(*block_start_tick)) < combat_settings.parry_ticks as i16 {
+        HitResult::Parried
+    }else {
+        HitResult::Blocked
+    }
+}
+
+pub fn apply_damage(
+    mut damage_events: EventReader<DamageEvent>,
+    mut combatant_query: Query<(&mut Health, &mut CurrentStates, &Position, &Rotation), (With<CombatantMarker>, With<InteractNetworkAble>)>,
+    mut knockback_events: EventWriter<ApplyKnockback>,
+    mut hit_resolved_events: EventWriter<HitResolvedEvent>,
+    mut messages: ServerMessages,
+    combat_settings: Res<CombatSettings>,
+    dash_settings: Res<DashSettings>,
+    tick_manager: Res<TickManager>,
+){
+    let tick = tick_manager.tick();
+
+    for event in damage_events.read() {
+        let attacker_position = event.attacker
+            .filter(|attacker| *attacker != event.target)
+            .and_then(|attacker| combatant_query.get(attacker).ok())
+            .map(|(_, _, position, _)| *position);
+        let Ok((mut health, mut current_states, position, rotation)) = combatant_query.get_mut(event.target) else {continue};
+
+        let result = resolve_hit(&current_states, position, rotation, attacker_position.as_ref(), tick, &combat_settings, &dash_settings);
+        let amount = match result {
+            HitResult::Hit => event.amount,
+            HitResult::Blocked => event.amount * (1.0 - combat_settings.block_damage_reduction),
+            HitResult::Parried | HitResult::Ignored => 0.0
+        };
+
+        health.current = (health.current - amount).max(0.0);
+
+        if health.current <= 0.0 && !current_states.0.contains_key(&States::Died) {
+            current_states.transition(&States::Died, StateInfos{
+                start: Some(tick.0),
+                values: None,
+                ..default()
+            });
+
+            messages.send_to_all::<GameplayChannel, KillNoticeMessage>(&mut KillNoticeMessage{
+                victim: event.target,
+                killer: event.attacker.filter(|attacker| *attacker != event.target),
+                tick: tick.0
+            });
+        }
+
+        if let (HitResult::Hit, Some(attacker_position)) = (result, attacker_position.as_ref()) {
+            let direction = (position.0 - attacker_position.0).with_y(0.0).normalize_or_zero();
+
+            knockback_events.send(ApplyKnockback{
+                target: event.target,
+                impulse: direction * combat_settings.knockback_strength,
+                stun_ticks: combat_settings.knockback_stun_ticks
+            });
+        }
+
+        if result == HitResult::Parried {
+            if let Some(Ok((_, mut attacker_states, _, _))) = event.attacker.map(|attacker| combatant_query.get_mut(attacker)) {
+                attacker_states.transition(&States::Staggered, StateInfos{
+                    start: Some(tick.0),
+                    values: Some(StatesValues::Staggered(tick.0)),
+                    ..default()
+                });
+            }
+        }
+
+        hit_resolved_events.send(HitResolvedEvent{
+            target: event.target,
+            attacker: event.attacker,
+            result,
+            amount
+        });
+
+        messages.send_to_all::<GameplayChannel, HitResultMessage>(&mut HitResultMessage{
+            target: event.target,
+            attacker: event.attacker,
+            result,
+            amount
+        });
+    }
+}
+
+fn apply_healing(
+    mut heal_events: EventReader<HealEvent>,
+    mut combatant_query: Query<(&mut Health, &CurrentStates), (With<CombatantMarker>, With<InteractNetworkAble>)>,
+){
+    for event in heal_events.read() {
+        let Ok((mut health, current_states)) = combatant_query.get_mut(event.target) else {continue};
+
+        if current_states.0.contains_key(&States::Died) {
+            continue;
+        }
+
+        health.current = (health.current + event.amount).min(health.max);
+    }
+}
@@ -0,0 +1,103 @@
+use avian3d::prelude::LinearVelocity;
+use bevy::app::{App, FixedUpdate, Plugin};
+use bevy::math::Vec3;
+use bevy::prelude::{Commands, Component, Entity, Event, EventReader, IntoSystemConfigs, Query, Reflect, Res, With};
+use lightyear::prelude::{Tick, TickManager};
+use lightyear::prelude::client::Rollback;
+use serde::{Deserialize, Serialize};
+use crate::{InteractNetworkAble, NetworkSide};
+use crate::plugins::combatant::CombatantMarker;
+use crate::plugins::health::apply_damage;
+use crate::plugins::shared::current_tick;
+use crate::systems::charactercontroller::{character_walk, check_is_grounded};
+
+pub struct KnockbackPlugin{
+    pub network_side: NetworkSide
+}
+
+#[derive(Component, Serialize, Deserialize, Clone, Debug, PartialEq, Reflect)]
+pub struct Knockback{
+    pub impulse: Vec3,
+    pub tick: u16,
+    pub stun_ticks: u16,
+    pub applied: bool
+}
+
+#[derive(Event, Clone, Debug)]
+pub struct ApplyKnockback{
+    pub target: Entity,
+    pub impulse: Vec3,
+    pub stun_ticks: u16
+}
+
+impl Knockback {
+    pub fn is_stunned(&self, tick: Tick) -> bool{
+        let elapsed = tick - Tick(self.tick);
+
+        0 <= elapsed && elapsed < self.stun_ticks as i16
+    }
+}
+
+impl Plugin for KnockbackPlugin {
+    fn build(&self, app: &mut App) {
+        app.register_type::<Knockback>();
+        app.add_event::<ApplyKnockback>();
+
+        if self.network_side == NetworkSide::Server {
+            app.add_systems(FixedUpdate,apply_knockback.after(apply_damage));
+        }
+
+        app.add_systems(FixedUpdate,character_knockback.after(check_is_grounded).before(character_walk));
+    }
+}
+
+pub fn is_stunned(knockback: Option<&Knockback>, tick: Tick) -> bool{
+    knockback.is_some_and(|knockback| knockback.is_stunned(tick))
+}
+
+fn apply_knockback(
+    mut commands: Commands,
+    mut knockback_events: EventReader<ApplyKnockback>,
+    combatant_query: Query<(), (With<CombatantMarker>, With<InteractNetworkAble>)>,
+    tick_manager: Res<TickManager>,
+){
+    let tick = tick_manager.tick();
+
+    for event in knockback_events.read() {
+        if combatant_query.get(event.target).is_err() {
+            continue;
+        }
+
+        commands.entity(event.target).insert(Knockback{
+            impulse: event.impulse,
+            tick: tick.0,
+            stun_ticks: event.stun_ticks,
+            applied: false
+        });
+    }
+}
+
+pub fn character_knockback(
+    mut commands: Commands,
+    mut character_query: Query<(Entity, &mut Knockback, &mut LinearVelocity), With<InteractNetworkAble>>,
+    tick_manager: Res<TickManager>,
+    rollback: Option<Res<Rollback>>,
+){
+    let tick = current_tick(&tick_manager, rollback.as_deref());
+
+    for (entity, mut knockback, mut linear_velocity) in character_query.iter_mut() {
+        let elapsed = tick - Tick(knockback.tick);
+
+        if knockback.applied && elapsed >= knockback.stun_ticks as i16 {
+            commands.entity(entity).remove::<Knockback>();
+            continue;
+        }
+
+        if knockback.applied || elapsed < 0 {
+            continue;
+        }
+
+        linear_velocity.0 += knockback.impulse;
+        knockback.applied = true;
+    }
+}
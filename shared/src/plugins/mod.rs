@@ -1,3 +1,10 @@
 pub mod shared;
 pub mod statesmachine;
-pub mod combatant;
\ No newline at end of file
+pub mod combatant;
+pub mod spawnpoints;
+pub mod health;
+pub mod platforms;
+pub mod knockback;
+pub mod diagnostics;
+pub mod netstate;
+pub mod projectile;
\ No newline at end of file
@@ -0,0 +1,237 @@
+use std::f32::consts::TAU;
+use avian3d::prelude::{LinearVelocity, PhysicsSet, Position, Rotation};
+use bevy::app::{App, FixedPostUpdate, FixedPreUpdate, Plugin, Update};
+use bevy::diagnostic::DiagnosticsStore;
+use bevy::log::info;
+use bevy::math::{EulerRot, Quat, Vec3};
+use bevy::prelude::{Added, Commands, Component, Entity, IntoSystemConfigs, Local, Query, Reflect, ReflectComponent, Res, Resource, Time, Transform, With, Without};
+use lightyear::prelude::{ComponentReplicationOverrides, Deserialize, IoDiagnosticsPlugin, Serialize};
+use lightyear::prelude::client::{InterpolationSet, Interpolated, Predicted, PredictionSet};
+use lightyear::prelude::server::ConnectionManager;
+use crate::{InteractNetworkAble, NetworkSide};
+use crate::plugins::combatant::{CharacterController, CombatantMarker};
+
+pub const POSITION_SCALE: f32 = 1000.0;
+pub const VELOCITY_SCALE: f32 = 100.0;
+pub const YAW_STEPS: f32 = 65536.0;
+pub const BANDWIDTH_LOG_INTERVAL: f32 = 10.0;
+
+pub struct NetStatePlugin{
+    pub network_side: NetworkSide,
+    pub compact_combatant_state: bool
+}
+
+#[derive(Resource)]
+pub struct CompactCombatantState(pub bool);
+
+#[derive(Component, Serialize, Deserialize, Clone, Copy, Debug, Default, PartialEq, Eq, Reflect)]
+#[reflect(Component)]
+pub struct CombatantNetState{
+    pub position: [i32; 3],
+    pub yaw: u16,
+    pub velocity: [i16; 3],
+    pub grounded: bool
+}
+
+impl CombatantNetState {
+    pub fn new(position: Vec3, rotation: Quat, velocity: Vec3, grounded: bool) -> Self{
+        let (yaw, _, _) = rotation.to_euler(EulerRot::YXZ);
+
+        Self{
+            position: (position * POSITION_SCALE).round().as_ivec3().to_array(),
+            yaw: ((yaw / TAU).rem_euclid(1.0) * YAW_STEPS).round() as u32 as u16,
+            velocity: (velocity * VELOCITY_SCALE).round().clamp(Vec3::splat(i16::MIN as f32), Vec3::splat(i16::MAX as f32)).as_ivec3().to_array().map(|value| value as i16),
+            grounded
+        }
+    }
+
+    pub fn position(&self) -> Vec3{
+        Vec3::new(self.position[0] as f32, self.position[1] as f32, self.position[2] as f32) / POSITION_SCALE
+    }
+
+    pub fn yaw(&self) -> f32{
+        self.yaw as f32 / YAW_STEPS * TAU
+    }
+
+    pub fn rotation(&self) -> Quat{
+        Quat::from_rotation_y(self.yaw())
+    }
+
+    pub fn velocity(&self) -> Vec3{
+        Vec3::new(self.velocity[0] as f32, self.velocity[1] as f32, self.velocity[2] as f32) / VELOCITY_SCALE
+    }
+
+    pub fn lerp(start: &Self, other: &Self, t: f32) -> Self{
+        let yaw_delta = (other.yaw() - start.yaw() + TAU * 1.5).rem_euclid(TAU) - TAU * 0.5;
+
+        Self::new(
+            start.position().lerp(other.position(), t),
+            Quat::from_rotation_y(start.yaw() + yaw_delta * t),
+            start.velocity().lerp(other.velocity(), t),
+            if t < 0.5 {start.grounded} else {other.grounded}
+        )
+    }
+}
+
+impl Plugin for NetStatePlugin {
+    fn build(&self, app: &mut App) {
+        app.register_type::<CombatantNetState>();
+        app.insert_resource(CompactCombatantState(self.compact_combatant_state));
+
+        if self.network_side == NetworkSide::Server {
+            app.add_systems(Update,log_replication_bandwidth);
+        }
+
+        if !self.compact_combatant_state {
+            return;
+        }
+
+        if self.network_side == NetworkSide::Client {
+            app.add_systems(FixedPreUpdate,apply_combatant_net_state);
+            app.add_systems(FixedPostUpdate,write_combatant_net_state.after(PhysicsSet::Sync).before(PredictionSet::UpdateHistory));
+            app.add_systems(Update,apply_interpolated_net_state.after(InterpolationSet::Interpolate));
+        }else {
+            app.add_systems(Update,attach_combatant_net_state);
+            app.add_systems(FixedPostUpdate,write_combatant_net_state.after(PhysicsSet::Sync));
+        }
+    }
+}
+
+fn attach_combatant_net_state(
+    mut commands: Commands,
+    combatant_query: Query<(Entity, &Transform, &CharacterController), (Added<CombatantMarker>, With<InteractNetworkAble>)>,
+){
+    for (entity, transform, controller) in combatant_query.iter() {
+        commands.entity(entity).insert((
+            CombatantNetState::new(transform.translation, transform.rotation, Vec3::ZERO, controller.grounded),
+            ComponentReplicationOverrides::<Position>::default().disable_all(),
+            ComponentReplicationOverrides::<Rotation>::default().disable_all(),
+        ));
+    }
+}
+
+fn apply_combatant_net_state(
+    mut combatant_query: Query<(&CombatantNetState, &mut Position, &mut Rotation, &mut LinearVelocity), (With<InteractNetworkAble>, With<Predicted>)>,
+){
+    for (net_state, mut position, mut rotation, mut linear_velocity) in combatant_query.iter_mut() {
+        let net_position = net_state.position();
+        let net_rotation = net_state.rotation();
+        let net_velocity = net_state.velocity();
+
+        if position.0 != net_position {
+            position.0 = net_position;
+        }
+
+        if rotation.0 != net_rotation {
+            rotation.0 = net_rotation;
+        }
+
+        if linear_velocity.0 != net_velocity {
+            linear_velocity.0 = net_velocity;
+        }
+    }
+}
+
+fn write_combatant_net_state(
+    mut combatant_query: Query<(&mut CombatantNetState, &Position, &Rotation, &LinearVelocity, &CharacterController), (With<InteractNetworkAble>, Without<Interpolated>)>,
+){
+    for (mut net_state, position, rotation, linear_velocity, controller) in combatant_query.iter_mut() {
+        let next_state = CombatantNetState::new(position.0, rotation.0, linear_velocity.0, controller.grounded);
+
+        if *net_state != next_state {
+            *net_state = next_state;
+        }
+    }
+}
+
+fn apply_interpolated_net_state(
+    mut combatant_query: Query<(&CombatantNetState, &mut Position, &mut Rotation, &mut LinearVelocity, &mut CharacterController), With<Interpolated>>,
+){
+    for (net_state, mut position, mut rotation, mut linear_velocity, mut controller) in combatant_query.iter_mut() {
+        position.0 = net_state.position();
+        rotation.0 = net_state.rotation();
+        linear_velocity.0 = net_state.velocity();
+        controller.grounded = net_state.grounded;
+    }
+}
+
+fn log_replication_bandwidth(
+    mut timer: Local<f32>,
+    compact_combatant_state: Res<CompactCombatantState>,
+    combatant_query: Query<(), (With<CombatantMarker>, With<InteractNetworkAble>)>,
+    connection_manager: Res<ConnectionManager>,
+    diagnostics_store: Res<DiagnosticsStore>,
+    time: Res<Time>,
+){
+    *timer += time.delta_secs();
+
+    if *timer < BANDWIDTH_LOG_INTERVAL {
+        return;
+    }
+
+    *timer = 0.0;
+
+    let combatants = combatant_query.iter().count();
+    let clients = connection_manager.connected_clients().count();
+    let Some(bytes_out) = diagnostics_store.get(&IoDiagnosticsPlugin::BYTES_OUT).and_then(|diagnostic| diagnostic.smoothed()) else {return};
+
+    if combatants == 0 || clients == 0 {
+        return;
+    }
+
+    info!(
+        "Replication out (compact state: {}): {:.0} bytes/s measured for {} combatants and {} clients",
+        compact_combatant_state.0,
+        bytes_out,
+        combatants,
+        clients
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn yaw_distance(a: f32, b: f32) -> f32{
+        ((a - b + TAU * 1.5).rem_euclid(TAU) - TAU * 0.5).abs()
+    }
+
+    #[test]
+    fn position_and_velocity_round_trip_within_quantization_step() {
+        let position = Vec3::new(12.3456, -0.0004, -98.7654);
+        let velocity = Vec3::new(-3.217, 9.81, 0.004);
+        let net_state = CombatantNetState::new(position, Quat::IDENTITY, velocity, true);
+
+        assert!((net_state.position() - position).abs().max_element() <= 0.5 / POSITION_SCALE);
+        assert!((net_state.velocity() - velocity).abs().max_element() <= 0.5 / VELOCITY_SCALE);
+        assert!(net_state.grounded);
+    }
+
+    #[test]
+    fn yaw_round_trips_including_negative_angles() {
+        for yaw in [0.0, 1.0, -1.0, 3.1, -3.1, std::f32::consts::PI] {
+            let net_state = CombatantNetState::new(Vec3::ZERO, Quat::from_rotation_y(yaw), Vec3::ZERO, false);
+
+            assert!(yaw_distance(net_state.yaw(), yaw) <= TAU / YAW_STEPS);
+        }
+    }
+
+    #[test]
+    fn velocity_saturates_instead_of_wrapping() {
+        let net_state = CombatantNetState::new(Vec3::ZERO, Quat::IDENTITY, Vec3::new(1000.0, -1000.0, 0.0), false);
+
+        assert_eq!(net_state.velocity[0], i16::MAX);
+        assert_eq!(net_state.velocity[1], i16::MIN);
+    }
+
+    #[test]
+    fn lerp_takes_shortest_yaw_arc() {
+        let start = CombatantNetState::new(Vec3::ZERO, Quat::from_rotation_y(3.0), Vec3::ZERO, false);
+        let end = CombatantNetState::new(Vec3::X, Quat::from_rotation_y(-3.0), Vec3::ZERO, true);
+        let middle = CombatantNetState::lerp(&start, &end, 0.5);
+
+        assert!(yaw_distance(middle.yaw(), std::f32::consts::PI) <= 2.0 * TAU / YAW_STEPS);
+        assert!((middle.position() - Vec3::new(0.5, 0.0, 0.0)).length() <= 1.0 / POSITION_SCALE);
+        assert!(middle.grounded);
+    }
+}
@@ -0,0 +1,187 @@
+use avian3d::prelude::{AngularVelocity, Collider, CollisionLayers, LinearVelocity, RigidBody};
+use bevy::app::{App, FixedUpdate, Plugin};
+use bevy::math::Vec3;
+use bevy::prelude::{Bundle, Component, Fixed, InheritedVisibility, IntoSystemConfigs, Query, Reflect, ReflectComponent, Res, Time, Transform, With};
+use lightyear::prelude::{Deserialize, NetworkTarget, Serialize, Tick, TickManager};
+use lightyear::prelude::client::Rollback;
+use lightyear::prelude::server::{Replicate, SyncTarget, VisibilityMode};
+use crate::{GameMask, InteractNetworkAble, NetworkSide};
+use crate::plugins::shared::current_tick;
+use crate::protocol::REPLICATION_GROUP;
+use crate::systems::charactercontroller::check_is_grounded;
+
+pub struct PlatformsPlugin;
+
+#[derive(Component, Serialize, Deserialize, Clone, Debug, PartialEq, Reflect)]
+#[reflect(Component)]
+pub struct MovingPlatform{
+    pub size: Vec3,
+    pub travel: Vec3,
+    pub travel_ticks: u16,
+    pub angular_speed: f32,
+    pub cycle_start_tick: u16
+}
+
+#[derive(Bundle)]
+pub struct MovingPlatformServerBundle{
+    moving_platform: MovingPlatform,
+    rigid_body: RigidBody,
+    collider: Collider,
+    collision_layers: CollisionLayers,
+    transform: Transform,
+    replicate: Replicate,
+    network_side: NetworkSide,
+    inherited_visibility: InheritedVisibility,
+    interact_network_able: InteractNetworkAble
+}
+
+#[derive(Bundle)]
+pub struct MovingPlatformClientBundle{
+    rigid_body: RigidBody,
+    collider: Collider,
+    collision_layers: CollisionLayers,
+    network_side: NetworkSide,
+    inherited_visibility: InheritedVisibility,
+    interact_network_able: InteractNetworkAble
+}
+
+impl Default for MovingPlatform {
+    fn default() -> Self {
+        Self {
+            size: Vec3::new(3.0, 0.2, 3.0),
+            travel: Vec3::ZERO,
+            travel_ticks: 256,
+            angular_speed: 0.0,
+            cycle_start_tick: 0
+        }
+    }
+}
+
+impl MovingPlatform {
+    pub fn advance(&mut self, tick: Tick) -> bool{
+        let travel_ticks = self.travel_ticks.clamp(1, i16::MAX as u16 / 2);
+        let cycle_ticks = travel_ticks as i32 * 2;
+        let elapsed = (tick - Tick(self.cycle_start_tick)) as i32;
+        let completed_cycles = elapsed.div_euclid(cycle_ticks);
+
+        if completed_cycles != 0 {
+            self.cycle_start_tick = self.cycle_start_tick.wrapping_add((completed_cycles * cycle_ticks) as u16);
+        }
+
+        elapsed.rem_euclid(cycle_ticks) < travel_ticks as i32
+    }
+}
+
+impl MovingPlatformServerBundle {
+    pub fn new(moving_platform: MovingPlatform, transform: Transform) -> Self{
+        Self{
+            rigid_body: RigidBody::Kinematic,
+            collider: Collider::cuboid(moving_platform.size.x, moving_platform.size.y, moving_platform.size.z),
+            collision_layers: GameMask::floor_layers(),
+            moving_platform,
+            transform,
+            replicate: Replicate {
+                group: REPLICATION_GROUP,
+                sync: SyncTarget {
+                    prediction: NetworkTarget::All,
+                    ..Default::default()
+                },
+                visibility: VisibilityMode::InterestManagement,
+                ..Default::default()
+            },
+            network_side: NetworkSide::Server,
+            inherited_visibility: InheritedVisibility::VISIBLE,
+            interact_network_able: InteractNetworkAble
+        }
+    }
+}
+
+impl MovingPlatformClientBundle {
+    pub fn new(moving_platform: &MovingPlatform) -> Self{
+        Self{
+            rigid_body: RigidBody::Kinematic,
+            collider: Collider::cuboid(moving_platform.size.x, moving_platform.size.y, moving_platform.size.z),
+            collision_layers: GameMask::floor_layers(),
+            network_side: NetworkSide::Client,
+            inherited_visibility: InheritedVisibility::VISIBLE,
+            interact_network_able: InteractNetworkAble
+        }
+    }
+}
+
+impl Plugin for PlatformsPlugin {
+    fn build(&self, app: &mut App) {
+        app.register_type::<MovingPlatform>();
+        app.add_systems(FixedUpdate,move_platforms.before(check_is_grounded));
+    }
+}
+
+pub fn move_platforms(
+    mut platform_query: Query<(&mut MovingPlatform, &mut LinearVelocity, &mut AngularVelocity), With<InteractNetworkAble>>,
+    tick_manager: Res<TickManager>,
+    rollback: Option<Res<Rollback>>,
+    time_fixed: Res<Time<Fixed>>,
+){
+    let tick = current_tick(&tick_manager, rollback.as_deref());
+    let timestep = time_fixed.timestep().as_secs_f32();
+
+    for (mut moving_platform, mut linear_velocity, mut angular_velocity) in platform_query.iter_mut() {
+        let forward = moving_platform.advance(tick);
+        let travel_velocity = moving_platform.travel / (moving_platform.travel_ticks.clamp(1, i16::MAX as u16 / 2) as f32 * timestep);
+        let target_linear_velocity = if forward {travel_velocity} else {-travel_velocity};
+        let target_angular_velocity = Vec3::Y * moving_platform.angular_speed;
+
+        if linear_velocity.0 != target_linear_velocity {
+            linear_velocity.0 = target_linear_velocity;
+        }
+
+        if angular_velocity.0 != target_angular_velocity {
+            angular_velocity.0 = target_angular_velocity;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn platform(travel_ticks: u16, cycle_start_tick: u16) -> MovingPlatform{
+        MovingPlatform{
+            travel_ticks,
+            cycle_start_tick,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn alternates_direction_every_travel_period() {
+        let mut moving_platform = platform(10, 0);
+
+        assert!(moving_platform.advance(Tick(0)));
+        assert!(moving_platform.advance(Tick(9)));
+        assert!(!moving_platform.advance(Tick(10)));
+        assert!(!moving_platform.advance(Tick(19)));
+        assert!(moving_platform.advance(Tick(20)));
+        assert_eq!(moving_platform.cycle_start_tick, 20);
+    }
+
+    #[test]
+    fn keeps_phase_across_tick_wrap() {
+        let mut moving_platform = platform(10, u16::MAX - 4);
+
+        assert!(moving_platform.advance(Tick(u16::MAX)));
+        assert!(!moving_platform.advance(Tick(5)));
+        assert!(moving_platform.advance(Tick(15)));
+        assert_eq!(moving_platform.cycle_start_tick, 15);
+    }
+
+    #[test]
+    fn catches_up_after_long_gaps_and_rewinds_for_rollback() {
+        let mut moving_platform = platform(10, 0);
+
+        assert!(!moving_platform.advance(Tick(20_015)));
+        assert_eq!(moving_platform.cycle_start_tick, 20_000);
+        assert!(!moving_platform.advance(Tick(19_995)));
+        assert_eq!(moving_platform.cycle_start_tick, 19_980);
+    }
+}
@@ -0,0 +1,251 @@
+use avian3d::prelude::{Collider, CollisionLayers, LinearVelocity, Position, RigidBody, Rotation, Sensor, SpatialQuery, SpatialQueryFilter};
+use bevy::app::{App, FixedUpdate, Plugin, PreUpdate};
+use bevy::math::{Quat, Vec3};
+use bevy::prelude::{Added, Bundle, Commands, Component, Entity, EventReader, EventWriter, InheritedVisibility, IntoSystemConfigs, Local, Query, Reflect, ReflectComponent, ReflectResource, Res, ResMut, Resource, Transform, With, Without};
+use bevy::utils::hashbrown::HashMap;
+use leafwing_input_manager::prelude::ActionState;
+use lightyear::prelude::{ClientId, Deserialize, Serialize, Tick, TickManager};
+use lightyear::prelude::client::{Interpolated, Rollback};
+use crate::{GameMask, InteractNetworkAble, NetworkSide};
+use crate::plugins::combatant::{CombatantMarker, CombatantsList, PlayerCombatant};
+use crate::plugins::health::DamageEvent;
+use crate::plugins::knockback::{is_stunned, Knockback};
+use crate::plugins::statesmachine::{CurrentStates, States};
+use crate::protocol::{accept_pre_predicted, pre_predicted_spawn, reject_pre_predicted, CharacterAction, PrePredictedSpawned};
+
+pub struct ProjectilePlugin{
+    pub network_side: NetworkSide
+}
+
+#[derive(Component, Serialize, Deserialize, Clone, Debug, PartialEq, Reflect)]
+#[reflect(Component)]
+pub struct Projectile{
+    pub origin: Vec3,
+    pub velocity: Vec3,
+    pub spawn_tick: u16
+}
+
+#[derive(Component, Clone, Copy, Debug)]
+pub struct ProjectileOwner(pub Entity);
+
+#[derive(Resource, Clone, Debug, Reflect)]
+#[reflect(Resource)]
+pub struct ProjectileSettings{
+    pub speed: f32,
+    pub radius: f32,
+    pub damage: f32,
+    pub lifetime_ticks: u16,
+    pub cooldown_ticks: u16,
+    pub confirm_timeout_ticks: u16,
+    pub max_spawn_distance: f32,
+    pub max_spawn_tick_drift: u16
+}
+
+#[derive(Resource, Default, Debug)]
+pub struct ProjectileCooldowns(pub HashMap<ClientId,u16>);
+
+#[derive(Bundle)]
+pub struct ProjectileBundle{
+    rigid_body: RigidBody,
+    collider: Collider,
+    sensor: Sensor,
+    collision_layers: CollisionLayers,
+    linear_velocity: LinearVelocity,
+    transform: Transform,
+    game_mask: GameMask,
+    network_side: NetworkSide,
+    inherited_visibility: InheritedVisibility,
+    interact_network_able: InteractNetworkAble
+}
+
+impl Default for ProjectileSettings {
+    fn default() -> Self {
+        Self {
+            speed: 20.0,
+            radius: 0.1,
+            damage: 10.0,
+            lifetime_ticks: 128,
+            cooldown_ticks: 32,
+            confirm_timeout_ticks: 64,
+            max_spawn_distance: 2.0,
+            max_spawn_tick_drift: 32
+        }
+    }
+}
+
+impl ProjectileBundle {
+    pub fn new(projectile: &Projectile, radius: f32, network_side: NetworkSide) -> Self{
+        Self{
+            rigid_body: RigidBody::Kinematic,
+            collider: Collider::sphere(radius),
+            sensor: Sensor,
+            collision_layers: CollisionLayers::new(GameMask::Projectile, [GameMask::Default, GameMask::Floor, GameMask::Prop, GameMask::Combatant]),
+            linear_velocity: LinearVelocity(projectile.velocity),
+            transform: Transform::from_translation(projectile.origin),
+            game_mask: GameMask::Projectile,
+            network_side,
+            inherited_visibility: InheritedVisibility::VISIBLE,
+            interact_network_able: InteractNetworkAble
+        }
+    }
+}
+
+impl Plugin for ProjectilePlugin {
+    fn build(&self, app: &mut App) {
+        app.register_type::<Projectile>();
+        app.register_type::<ProjectileSettings>();
+        app.init_resource::<ProjectileSettings>();
+
+        if self.network_side == NetworkSide::Client {
+            app.add_systems(FixedUpdate,fire_projectile);
+            app.add_systems(PreUpdate,client_projectile_added);
+        }else {
+            app.init_resource::<ProjectileCooldowns>();
+            app.add_systems(FixedUpdate,(validate_projectiles,projectile_hits,expire_projectiles).chain());
+        }
+    }
+}
+
+fn fire_projectile(
+    mut commands: Commands,
+    combatant_query: Query<(&ActionState<CharacterAction>, &Position, &Rotation, Option<&Knockback>, &CurrentStates), (With<PlayerCombatant>, With<InteractNetworkAble>)>,
+    mut last_fire_tick: Local<Option<u16>>,
+    settings: Res<ProjectileSettings>,
+    tick_manager: Res<TickManager>,
+    rollback: Option<Res<Rollback>>,
+){
+    if rollback.as_ref().is_some_and(|rollback| rollback.is_rollback()) {
+        return;
+    }
+
+    let tick = tick_manager.tick();
+
+    for (action_state, position, rotation, knockback, current_states) in combatant_query.iter() {
+        if !action_state.just_pressed(&CharacterAction::Fire) || is_stunned(knockback, tick) || current_states.0.contains_key(&States::Died) {
+            continue;
+        }
+
+        if last_fire_tick.is_some_and(|last_tick| (tick - Tick(last_tick)) < settings.cooldown_ticks as i16) {
+            continue;
+        }
+
+        let projectile = new_projectile(position.0, rotation.0, tick, &settings);
+
+        commands.spawn((
+            ProjectileBundle::new(&projectile, settings.radius, NetworkSide::Client),
+            pre_predicted_spawn(tick, settings.confirm_timeout_ticks),
+            projectile,
+        ));
+
+        *last_fire_tick = Some(tick.0);
+    }
+}
+
+fn client_projectile_added(
+    mut commands: Commands,
+    projectile_query: Query<(Entity, &Projectile), (Added<Interpolated>, Without<NetworkSide>)>,
+    settings: Res<ProjectileSettings>,
+){
+    for (entity, projectile) in projectile_query.iter() {
+        commands.entity(entity).insert(ProjectileBundle::new(projectile, settings.radius, NetworkSide::Client));
+    }
+}
+
+fn validate_projectiles(
+    mut commands: Commands,
+    mut spawned_events: EventReader<PrePredictedSpawned<Projectile>>,
+    mut cooldowns: ResMut<ProjectileCooldowns>,
+    projectile_query: Query<&Projectile>,
+    combatant_query: Query<(&Position, Option<&Knockback>, &CurrentStates), (With<CombatantMarker>, With<InteractNetworkAble>)>,
+    combatants_list: Res<CombatantsList>,
+    settings: Res<ProjectileSettings>,
+    tick_manager: Res<TickManager>,
+){
+    let tick = tick_manager.tick();
+
+    for event in spawned_events.read() {
+        let Ok(projectile) = projectile_query.get(event.entity) else {continue};
+        let owner = combatants_list.0.iter()
+            .find(|(_, client_id)| **client_id == Some(event.client_id))
+            .map(|(entity, _)| *entity);
+
+        let valid = owner
+            .and_then(|owner| combatant_query.get(owner).ok())
+            .is_some_and(|(position, knockback, current_states)| {
+                !is_stunned(knockback, tick)
+                    && !current_states.0.contains_key(&States::Died)
+                    && projectile.origin.distance(position.0) <= settings.max_spawn_distance
+                    && projectile.velocity.length() <= settings.speed * 1.01
+                    && (tick - Tick(projectile.spawn_tick)).unsigned_abs() <= settings.max_spawn_tick_drift
+            })
+            && !cooldowns.0.get(&event.client_id).is_some_and(|last_tick| (tick - Tick(*last_tick)) < settings.cooldown_ticks as i16);
+
+        let Some(owner) = owner.filter(|_| valid) else {
+            reject_pre_predicted(&mut commands, event.entity);
+            continue;
+        };
+
+        cooldowns.0.insert(event.client_id, tick.0);
+        accept_pre_predicted(&mut commands, event.entity, event.client_id);
+        commands.entity(event.entity).insert((
+            ProjectileBundle::new(projectile, settings.radius, NetworkSide::Server),
+            ProjectileOwner(owner),
+        ));
+    }
+}
+
+fn projectile_hits(
+    mut commands: Commands,
+    mut damage_events: EventWriter<DamageEvent>,
+    projectile_query: Query<(Entity, &Position, &ProjectileOwner), (With<Projectile>, With<InteractNetworkAble>)>,
+    combatant_query: Query<(), With<CombatantMarker>>,
+    spatial_query: SpatialQuery,
+    settings: Res<ProjectileSettings>,
+){
+    let shape = Collider::sphere(settings.radius);
+
+    for (entity, position, owner) in projectile_query.iter() {
+        let filter = SpatialQueryFilter::from_mask([GameMask::Default, GameMask::Floor, GameMask::Prop, GameMask::Combatant])
+            .with_excluded_entities([entity, owner.0]);
+        let hits = spatial_query.shape_intersections(&shape, position.0, Quat::IDENTITY, &filter);
+
+        if hits.is_empty() {
+            continue;
+        }
+
+        if let Some(target) = hits.iter().find(|hit| combatant_query.contains(**hit)) {
+            damage_events.send(DamageEvent{
+                target: *target,
+                attacker: Some(owner.0),
+                amount: settings.damage
+            });
+        }
+
+        commands.entity(entity).despawn();
+    }
+}
+
+fn expire_projectiles(
+    mut commands: Commands,
+    projectile_query: Query<(Entity, &Projectile), (With<ProjectileOwner>, With<InteractNetworkAble>)>,
+    settings: Res<ProjectileSettings>,
+    tick_manager: Res<TickManager>,
+){
+    let tick = tick_manager.tick();
+
+    for (entity, projectile) in projectile_query.iter() {
+        if (tick - Tick(projectile.spawn_tick)) >= settings.lifetime_ticks as i16 {
+            commands.entity(entity).despawn();
+        }
+    }
+}
+
+fn new_projectile(position: Vec3, rotation: Quat, tick: Tick, settings: &ProjectileSettings) -> Projectile{
+    let forward = (rotation * Vec3::Z).with_y(0.0).normalize_or_zero();
+
+    Projectile{
+        origin: position + forward * 0.6 + Vec3::Y * 0.4,
+        velocity: forward * settings.speed,
+        spawn_tick: tick.0
+    }
+}
@@ -3,9 +3,16 @@ use std::net::{IpAddr, Ipv4Addr, SocketAddr};
 use std::time::Duration;
 use avian3d::prelude::*;
 use avian3d::sync::{position_to_transform};
-use lightyear::prelude::{Key, SharedConfig, TickConfig};
+use lightyear::prelude::{Key, LinkConditionerConfig, SharedConfig, Tick, TickConfig, TickManager};
+use lightyear::prelude::client::Rollback;
 use crate::{InteractNetworkAble, NetworkSide};
 use crate::plugins::combatant::CombatantPlugin;
+use crate::plugins::health::HealthPlugin;
+use crate::plugins::knockback::KnockbackPlugin;
+use crate::plugins::netstate::NetStatePlugin;
+use crate::plugins::platforms::PlatformsPlugin;
+use crate::plugins::projectile::ProjectilePlugin;
+use crate::plugins::spawnpoints::SpawnPointsPlugin;
 use crate::plugins::statesmachine::StatesMachinePlugin;
 use crate::protocol::ProtocolPlugin;
 
@@ -16,31 +23,69 @@ pub const CLIENT_ADDR: SocketAddr = SocketAddr::new(IpAddr::V4(Ipv4Addr::LOCALHO
 pub const SERVER_ADDR: SocketAddr = SocketAddr::new(IpAddr::V4(Ipv4Addr::LOCALHOST), SERVER_PORT);
 pub const PROTOCOL_ID: u64 = 1;
 pub const PRIVATE_KEY: Key = [5; 32];
+pub const LINK_CONDITIONER_APPLY_DELAY: f32 = 1.0;
+pub const COMPACT_STATE_PROTOCOL_FLAG: u64 = 1 << 32;
 
 pub struct SharedPlugin{
     pub predict_all: bool,
+    pub compact_combatant_state: bool,
     pub network_side: NetworkSide
 }
 
 pub struct Settings{
     pub input_delay_ticks: u16,
     pub correction_ticks_factor: f32,
-    pub predict_all: bool
+    pub predict_all: bool,
+    pub compact_combatant_state: bool,
+    pub link_conditioner: LinkConditionerSettings
+}
+
+#[derive(Resource, Clone, Debug, Default, PartialEq, Reflect)]
+#[reflect(Resource)]
+pub struct LinkConditionerSettings{
+    pub enabled: bool,
+    pub latency_ms: u64,
+    pub jitter_ms: u64,
+    pub packet_loss: f32
 }
 
 impl Plugin for SharedPlugin{
     fn build(&self, app: &mut App) {
         app.add_plugins(ProtocolPlugin {
             predict_all: self.predict_all,
+            compact_combatant_state: self.compact_combatant_state,
             network_side: self.network_side.clone()
         });
 
+        app.add_plugins(NetStatePlugin{
+            network_side: self.network_side.clone(),
+            compact_combatant_state: self.compact_combatant_state
+        });
+
         app.add_plugins(StatesMachinePlugin);
 
         app.add_plugins(CombatantPlugin{
             network_side: self.network_side.clone(),
         });
 
+        app.add_plugins(SpawnPointsPlugin{
+            network_side: self.network_side.clone(),
+        });
+
+        app.add_plugins(HealthPlugin{
+            network_side: self.network_side.clone(),
+        });
+
+        app.add_plugins(KnockbackPlugin{
+            network_side: self.network_side.clone(),
+        });
+
+        app.add_plugins(PlatformsPlugin);
+
+        app.add_plugins(ProjectilePlugin{
+            network_side: self.network_side.clone(),
+        });
+
         app.add_plugins(
             PhysicsPlugins::default()
                 .build()
@@ -86,10 +131,78 @@ pub fn settings() -> Settings{
     Settings{
         input_delay_ticks: 0,
         correction_ticks_factor: 4.0,
-        predict_all: false
+        predict_all: false,
+        compact_combatant_state: std::env::args().any(|argument| argument == "--compact-state"),
+        link_conditioner: LinkConditionerSettings::from_args()
     }
 }
 
+pub fn protocol_id(compact_combatant_state: bool) -> u64{
+    if compact_combatant_state {PROTOCOL_ID | COMPACT_STATE_PROTOCOL_FLAG} else {PROTOCOL_ID}
+}
+
+impl LinkConditionerSettings {
+    pub fn from_args() -> Self{
+        Self::parse_args(std::env::args().skip(1))
+    }
+
+    pub fn parse_args(arguments: impl IntoIterator<Item = String>) -> Self{
+        let mut link_conditioner = LinkConditionerSettings::default();
+
+        for argument in arguments {
+            let Some((name, value)) = argument.split_once('=') else {continue};
+
+            let applied = match name {
+                "--latency" => value.parse().ok().map(|latency_ms| link_conditioner.latency_ms = latency_ms),
+                "--jitter" => value.parse().ok().map(|jitter_ms| link_conditioner.jitter_ms = jitter_ms),
+                "--loss" => value.parse::<f32>().ok().filter(|loss| (0.0..=1.0).contains(loss)).map(|loss| link_conditioner.packet_loss = loss),
+                _ => continue
+            };
+
+            if applied.is_none() {
+                warn!("Ignoring malformed link conditioner argument {}", argument);
+                continue;
+            }
+
+            link_conditioner.enabled = true;
+        }
+
+        link_conditioner
+    }
+
+    pub fn config(&self) -> Option<LinkConditionerConfig>{
+        if !self.enabled {
+            return None;
+        }
+
+        Some(LinkConditionerConfig{
+            incoming_latency: Duration::from_millis(self.latency_ms),
+            incoming_jitter: Duration::from_millis(self.jitter_ms),
+            incoming_loss: self.packet_loss.clamp(0.0, 1.0)
+        })
+    }
+}
+
+pub fn link_conditioner_settled(link_conditioner: &Res<LinkConditionerSettings>, pending_since: &mut Option<f32>, time: &Time) -> bool{
+    if link_conditioner.is_changed() && !link_conditioner.is_added() {
+        *pending_since = Some(time.elapsed_secs());
+        return false;
+    }
+
+    if !pending_since.is_some_and(|since| time.elapsed_secs() - since >= LINK_CONDITIONER_APPLY_DELAY) {
+        return false;
+    }
+
+    *pending_since = None;
+    true
+}
+
+pub fn current_tick(tick_manager: &TickManager, rollback: Option<&Rollback>) -> Tick{
+    rollback
+        .map(|rb| tick_manager.tick_or_rollback_tick(rb))
+        .unwrap_or(tick_manager.tick())
+}
+
 fn fix_transform(
     mut query: Query<(&mut Transform, &Position, &Rotation), (With<RigidBody>, With<InteractNetworkAble>)>
 ){
@@ -103,3 +216,38 @@ fn fix_transform(
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn parse(arguments: &[&str]) -> LinkConditionerSettings{
+        LinkConditionerSettings::parse_args(arguments.iter().map(|argument| argument.to_string()))
+    }
+
+    #[test]
+    fn parses_link_conditioner_arguments() {
+        let link_conditioner = parse(&["--latency=120", "--jitter=15", "--loss=0.05"]);
+
+        assert!(link_conditioner.enabled);
+        assert_eq!(link_conditioner.latency_ms, 120);
+        assert_eq!(link_conditioner.jitter_ms, 15);
+        assert_eq!(link_conditioner.packet_loss, 0.05);
+    }
+
+    #[test]
+    fn ignores_unrelated_arguments() {
+        assert_eq!(parse(&["--compact-state", "--name=client"]), LinkConditionerSettings::default());
+    }
+
+    #[test]
+    fn rejects_malformed_values() {
+        assert_eq!(parse(&["--latency=fast", "--jitter=-5", "--loss=1.5"]), LinkConditionerSettings::default());
+
+        let link_conditioner = parse(&["--latency=abc", "--jitter=10"]);
+
+        assert!(link_conditioner.enabled);
+        assert_eq!(link_conditioner.latency_ms, 0);
+        assert_eq!(link_conditioner.jitter_ms, 10);
+    }
+}
@@ -0,0 +1,319 @@
+use avian3d::prelude::{Collider, LinearVelocity, Position, SpatialQuery, SpatialQueryFilter};
+use bevy::app::{App, First, Plugin, Update};
+use bevy::ecs::system::SystemParam;
+use bevy::math::Vec3;
+use bevy::prelude::{Commands, Component, Entity, Event, EventReader, EventWriter, IntoSystemConfigs, ParamSet, Query, Reflect, ReflectComponent, ReflectResource, Res, ResMut, Resource, Time, Transform, With, Without};
+use lightyear::prelude::{Tick, TickManager};
+use crate::{GameMask, NetworkSide};
+use crate::plugins::combatant::{create_npc_combatant, create_player_combatant, CombatantMarker, Stamina, Team};
+use crate::plugins::health::Health;
+use crate::plugins::knockback::Knockback;
+use crate::plugins::statesmachine::{CurrentStates, States};
+
+const OCCUPIED_RADIUS: f32 = 0.6;
+const RESERVATION_SECONDS: f32 = 0.5;
+
+pub struct SpawnPointsPlugin{
+    pub network_side: NetworkSide
+}
+
+#[derive(Component, Clone, Debug, Default, Reflect)]
+#[reflect(Component)]
+#[require(Transform)]
+pub struct SpawnPoint{
+    pub teams: Vec<Team>
+}
+
+#[derive(Resource, Clone, Copy, Debug, Default, PartialEq, Reflect)]
+#[reflect(Resource)]
+pub enum SpawnPolicy{
+    Random,
+    #[default]
+    FarthestFromEnemies,
+    RoundRobin
+}
+
+#[derive(Resource, Clone, Debug, Reflect)]
+#[reflect(Resource)]
+pub struct RespawnSettings{
+    pub enabled: bool,
+    pub delay_ticks: u16
+}
+
+#[derive(Resource)]
+pub struct SpawnPointsState{
+    next_index: usize,
+    seed: u64
+}
+
+#[derive(Resource, Default)]
+pub struct SpawnReservations(pub Vec<(Vec3, Option<Team>, f32)>);
+
+#[derive(Event)]
+pub struct RespawnCombatant(pub Entity);
+
+#[derive(Component)]
+pub struct PendingRespawn;
+
+#[derive(SystemParam)]
+pub struct SpawnPointSelector<'w, 's>{
+    spatial_query: SpatialQuery<'w, 's>,
+    spawn_points: Query<'w, 's, (Entity, &'static SpawnPoint, &'static Transform)>,
+    combatants: Query<'w, 's, (Entity, &'static Position, Option<&'static Team>), With<CombatantMarker>>,
+    policy: Res<'w, SpawnPolicy>,
+    state: ResMut<'w, SpawnPointsState>,
+    reservations: ResMut<'w, SpawnReservations>,
+    time: Res<'w, Time>,
+}
+
+impl Default for RespawnSettings {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            delay_ticks: 192
+        }
+    }
+}
+
+impl Plugin for SpawnPointsPlugin {
+    fn build(&self, app: &mut App) {
+        app.register_type::<SpawnPoint>();
+        app.register_type::<SpawnPolicy>();
+        app.register_type::<RespawnSettings>();
+
+        if self.network_side == NetworkSide::Server {
+            app.init_resource::<SpawnPolicy>();
+            app.init_resource::<RespawnSettings>();
+            app.init_resource::<SpawnReservations>();
+            app.insert_resource(SpawnPointsState{
+                next_index: 0,
+                seed: 0x9E37_79B9_7F4A_7C15
+            });
+            app.add_event::<RespawnCombatant>();
+            app.add_systems(First,respawn_combatant.after(create_player_combatant).after(create_npc_combatant));
+            app.add_systems(Update,queue_respawns);
+        }
+    }
+}
+
+impl SpawnPoint {
+    pub fn accepts(&self, team: Option<&Team>) -> bool{
+        match team {
+            Some(team) => self.teams.is_empty() || self.teams.contains(team),
+            None => true
+        }
+    }
+}
+
+impl SpawnPointsState {
+    fn next_random(&mut self) -> u64{
+        self.seed ^= self.seed << 13;
+        self.seed ^= self.seed >> 7;
+        self.seed ^= self.seed << 17;
+        self.seed
+    }
+}
+
+impl SpawnPointSelector<'_, '_> {
+    pub fn select(
+        &mut self,
+        team: Option<&Team>,
+        ignored: Option<Entity>,
+        collider: &Collider
+    ) -> Option<Transform>{
+        let now = self.time.elapsed_secs();
+
+        self.reservations.0.retain(|(_, _, reserved_at)| now - reserved_at < RESERVATION_SECONDS);
+
+        let mut candidates: Vec<(Entity, Transform)> = self.spawn_points.iter()
+            .filter(|(_, spawn_point, _)| spawn_point.accepts(team))
+            .map(|(entity, _, transform)| (entity, *transform))
+            .collect();
+
+        if candidates.is_empty() {
+            return None;
+        }
+
+        candidates.sort_by_key(|(entity, _)| *entity);
+
+        let free_candidates: Vec<Transform> = candidates.iter()
+            .filter(|(_, transform)| !self.is_occupied(transform, ignored, collider))
+            .map(|(_, transform)| *transform)
+            .collect();
+
+        let candidates = if free_candidates.is_empty() {
+            candidates.into_iter().map(|(_, transform)| transform).collect()
+        }else {
+            free_candidates
+        };
+
+        let index = match *self.policy {
+            SpawnPolicy::Random => (self.state.next_random() % candidates.len() as u64) as usize,
+            SpawnPolicy::RoundRobin => {
+                let index = self.state.next_index % candidates.len();
+                self.state.next_index = self.state.next_index.wrapping_add(1);
+                index
+            },
+            SpawnPolicy::FarthestFromEnemies => self.farthest_from_enemies(&candidates, team, ignored)
+        };
+
+        Some(candidates[index])
+    }
+
+    pub fn reserve(&mut self, position: Vec3, team: Option<Team>){
+        let now = self.time.elapsed_secs();
+
+        self.reservations.0.push((position, team, now));
+    }
+
+    fn is_occupied(&self, transform: &Transform, ignored: Option<Entity>, collider: &Collider) -> bool{
+        if self.reservations.0.iter().any(|(position, _, _)| position.distance(transform.translation) < OCCUPIED_RADIUS) {
+            return true;
+        }
+
+        self.spatial_query.shape_intersections(
+            collider,
+            transform.translation,
+            transform.rotation,
+            &SpatialQueryFilter::from_mask(GameMask::Combatant)
+        ).iter().any(|entity| Some(*entity) != ignored)
+    }
+
+    fn farthest_from_enemies(&self, candidates: &[Transform], team: Option<&Team>, ignored: Option<Entity>) -> usize{
+        let enemies: Vec<Vec3> = self.combatants.iter()
+            .filter(|(entity, _, _)| Some(*entity) != ignored)
+            .filter(|(_, _, enemy_team)| team.is_none() || *enemy_team != team)
+            .map(|(_, position, _)| position.0)
+            .chain(self.reservations.0.iter()
+                .filter(|(_, reserved_team, _)| team.is_none() || reserved_team.as_ref() != team)
+                .map(|(position, _, _)| *position))
+            .collect();
+
+        farthest_index(candidates, &enemies)
+    }
+}
+
+fn farthest_index(candidates: &[Transform], enemies: &[Vec3]) -> usize{
+    if enemies.is_empty() {
+        return 0;
+    }
+
+    let mut best_index = 0;
+    let mut best_distance = f32::MIN;
+
+    for (index, transform) in candidates.iter().enumerate() {
+        let closest_enemy = enemies.iter()
+            .map(|enemy| enemy.distance_squared(transform.translation))
+            .fold(f32::MAX, f32::min);
+
+        if closest_enemy > best_distance {
+            best_distance = closest_enemy;
+            best_index = index;
+        }
+    }
+
+    best_index
+}
+
+fn respawn_combatant(
+    mut commands: Commands,
+    mut respawn_events: EventReader<RespawnCombatant>,
+    mut params: ParamSet<(
+        SpawnPointSelector,
+        Query<(&mut Position, &mut Transform, &mut LinearVelocity, &mut CurrentStates, &mut Health, &mut Stamina), (With<CombatantMarker>, Without<SpawnPoint>)>
+    )>,
+    combatant_query: Query<(&Collider, Option<&Team>), With<CombatantMarker>>,
+){
+    for event in respawn_events.read() {
+        let Ok((collider, team)) = combatant_query.get(event.0) else {continue};
+
+        commands.entity(event.0).remove::<PendingRespawn>();
+
+        let mut spawn_point_selector = params.p0();
+        let Some(spawn_transform) = spawn_point_selector.select(team, Some(event.0), collider) else {continue};
+
+        spawn_point_selector.reserve(spawn_transform.translation, team.copied());
+
+        let mut respawn_query = params.p1();
+        let Ok((mut position, mut transform, mut linear_velocity, mut current_states, mut health, mut stamina)) = respawn_query.get_mut(event.0) else {continue};
+
+        position.0 = spawn_transform.translation;
+        *transform = spawn_transform;
+        *linear_velocity = LinearVelocity::ZERO;
+        *current_states = CurrentStates::default();
+        health.current = health.max;
+        stamina.current = stamina.max;
+        commands.entity(event.0).remove::<Knockback>();
+    }
+}
+
+fn queue_respawns(
+    mut commands: Commands,
+    mut respawn_events: EventWriter<RespawnCombatant>,
+    combatant_query: Query<(Entity, &CurrentStates), (With<CombatantMarker>, Without<PendingRespawn>)>,
+    settings: Res<RespawnSettings>,
+    tick_manager: Res<TickManager>,
+){
+    if !settings.enabled {
+        return;
+    }
+
+    let tick = tick_manager.tick();
+
+    for (entity, current_states) in combatant_query.iter() {
+        let Some(died_tick) = current_states.0.get(&States::Died).and_then(|state_infos| state_infos.start) else {continue};
+
+        if (tick - Tick(died_tick)) >= settings.delay_ticks as i16 {
+            commands.entity(entity).insert(PendingRespawn);
+            respawn_events.send(RespawnCombatant(entity));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn candidates(positions: &[Vec3]) -> Vec<Transform>{
+        positions.iter().map(|position| Transform::from_translation(*position)).collect()
+    }
+
+    #[test]
+    fn spawn_point_accepts_listed_teams_only() {
+        let open_spawn = SpawnPoint{teams: vec![]};
+        let team_spawn = SpawnPoint{teams: vec![Team(1)]};
+
+        assert!(open_spawn.accepts(Some(&Team(0))));
+        assert!(open_spawn.accepts(None));
+        assert!(team_spawn.accepts(Some(&Team(1))));
+        assert!(!team_spawn.accepts(Some(&Team(0))));
+        assert!(team_spawn.accepts(None));
+    }
+
+    #[test]
+    fn picks_candidate_with_largest_distance_to_closest_enemy() {
+        let candidates = candidates(&[Vec3::ZERO, Vec3::new(10.0, 0.0, 0.0), Vec3::new(-6.0, 0.0, 0.0)]);
+        let enemies = [Vec3::new(1.0, 0.0, 0.0), Vec3::new(12.0, 0.0, 0.0)];
+
+        assert_eq!(farthest_index(&candidates, &enemies), 2);
+    }
+
+    #[test]
+    fn falls_back_to_first_candidate_without_enemies() {
+        let candidates = candidates(&[Vec3::ZERO, Vec3::new(10.0, 0.0, 0.0)]);
+
+        assert_eq!(farthest_index(&candidates, &[]), 0);
+    }
+
+    #[test]
+    fn random_sequence_is_deterministic_per_seed() {
+        let mut first = SpawnPointsState{next_index: 0, seed: 7};
+        let mut second = SpawnPointsState{next_index: 0, seed: 7};
+
+        let first_values: Vec<u64> = (0..4).map(|_| first.next_random()).collect();
+        let second_values: Vec<u64> = (0..4).map(|_| second.next_random()).collect();
+
+        assert_eq!(first_values, second_values);
+        assert_ne!(first_values[0], first_values[1]);
+    }
+}
@@ -1,4 +1,3 @@
-use std::time::{SystemTime, UNIX_EPOCH};
 use bevy::app::{App, FixedPreUpdate};
 use bevy::math::Vec3;
 use bevy::prelude::{Added, Changed, Commands, Component, Entity, Event, EventWriter, IntoSystemConfigs, Or, Plugin, Query, Reflect, With, Without};
@@ -17,7 +16,12 @@ pub struct StatesSettings{
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Reflect)]
 pub enum StatesValues{
-    Walking(Vec3)
+    Walking(Vec3),
+    Jumping(bool),
+    Crouching(bool),
+    Dashing(Vec3,u16),
+    Blocking(u16),
+    Staggered(u16)
 }
 
 #[derive(Event)]
@@ -30,7 +34,7 @@ pub struct StateRemoved(pub Entity,pub States);
 
 #[derive(Serialize, Deserialize, Clone, Debug, PartialEq, Reflect)]
 pub struct StateInfos{
-    pub start: Option<u128>,
+    pub start: Option<u16>,
     pub duration: f32,
     pub in_cooldown: bool,
     pub stopped_states: Vec<States>,
@@ -42,6 +46,11 @@ pub enum States{
     Idle,
     Walking,
     Jumping,
+    Sprinting,
+    Crouching,
+    Dashing,
+    Blocking,
+    Staggered,
     Died
 }
 
@@ -63,10 +72,7 @@ impl Plugin for StatesMachinePlugin {
 impl Default for StateInfos {
     fn default() -> Self {
         Self {
-            start: match SystemTime::now().duration_since(UNIX_EPOCH) {
-                Ok(duration_since) => {Some(duration_since.as_millis())},
-                Err(_) => {None}
-            },
+            start: None,
             duration: 0.0,
             in_cooldown: false,
             stopped_states: Vec::new(),
@@ -80,14 +86,14 @@ impl States {
         match self {
             States::Idle => {
                 StatesSettings {
-                    blacklist: vec![States::Died,States::Jumping],
+                    blacklist: vec![States::Died,States::Jumping,States::Dashing,States::Blocking,States::Staggered],
                     stop_list: vec![States::Walking],
                     stop_all: false
                 }
             },
             States::Walking => {
                 StatesSettings {
-                    blacklist: vec![States::Died,States::Jumping],
+                    blacklist: vec![States::Died,States::Dashing,States::Blocking,States::Staggered],
                     stop_list: vec![States::Idle],
                     stop_all: false
                 }
@@ -95,7 +101,42 @@ impl States {
             States::Jumping => {
                 StatesSettings {
                     blacklist: vec![States::Died],
-                    stop_list: vec![States::Idle,States::Walking],
+                    stop_list: vec![States::Idle],
+                    stop_all: false
+                }
+            },
+            States::Sprinting => {
+                StatesSettings {
+                    blacklist: vec![States::Died,States::Crouching,States::Dashing,States::Blocking,States::Staggered],
+                    stop_list: vec![],
+                    stop_all: false
+                }
+            },
+            States::Crouching => {
+                StatesSettings {
+                    blacklist: vec![States::Died,States::Dashing,States::Staggered],
+                    stop_list: vec![States::Sprinting],
+                    stop_all: false
+                }
+            },
+            States::Dashing => {
+                StatesSettings {
+                    blacklist: vec![States::Died,States::Staggered],
+                    stop_list: vec![States::Idle,States::Walking,States::Sprinting,States::Blocking],
+                    stop_all: false
+                }
+            },
+            States::Blocking => {
+                StatesSettings {
+                    blacklist: vec![States::Died,States::Dashing,States::Staggered],
+                    stop_list: vec![States::Idle,States::Walking,States::Sprinting],
+                    stop_all: false
+                }
+            },
+            States::Staggered => {
+                StatesSettings {
+                    blacklist: vec![States::Died],
+                    stop_list: vec![States::Idle,States::Walking,States::Sprinting,States::Dashing,States::Blocking],
                     stop_all: false
                 }
             },
@@ -154,13 +195,18 @@ impl CurrentStates {
         let current_states = &mut self.0;
 
         for state in settings.stop_list.iter() {
-            stopped_states.push(state.clone());
-            current_states.remove(state);
+            if current_states.remove(state).is_some() {
+                stopped_states.push(state.clone());
+            }
         }
 
         state_infos.stopped_states = stopped_states;
         current_states.insert(transition_state.clone(), state_infos);
     }
+
+    pub fn stop(&mut self, state: &States) -> Option<StateInfos>{
+        self.0.remove(state)
+    }
 }
 
 pub fn current_states_added(
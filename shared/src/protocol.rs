@@ -1,43 +1,434 @@
 use avian3d::prelude::{AngularVelocity, ComputedMass, ExternalForce, ExternalImpulse, GravityScale, LinearVelocity, Position, Rotation};
+use std::fmt::Debug;
+use std::marker::PhantomData;
 use bevy::app::App;
-use bevy::prelude::{default, Component, Plugin, Reflect, Transform};
+use bevy::ecs::entity::MapEntities;
+use bevy::ecs::system::SystemParam;
+use bevy::math::Vec3;
+use bevy::prelude::{default, Added, Commands, Component, Entity, EntityMapper, Event, EventReader, EventWriter, FixedUpdate, Plugin, PreUpdate, Query, Reflect, Res, ResMut, Transform, Update, With};
 use leafwing_input_manager::{Actionlike, InputControlKind};
-use lightyear::prelude::{AppComponentExt, ChannelDirection, InputConfig, LeafwingInputPlugin, ReplicationGroup};
-use lightyear::prelude::client::{ComponentSyncMode, LerpFn};
+use lightyear::prelude::{AppChannelExt, AppComponentExt, AppMessageExt, Channel, ChannelDirection, ChannelMode, ChannelSettings, ClientId, InputConfig, LeafwingInputPlugin, Message, NetworkTarget, PrePredicted, ReliableSettings, Replicated, ReplicationGroup, Tick, TickManager};
+use lightyear::prelude::client::{ClientReceiveMessage, ComponentSyncMode, LerpFn, Predicted};
+use lightyear::prelude::server::{ControlledBy, RoomId, RoomManager, ServerReceiveMessage, SyncTarget, VisibilityMode};
+use lightyear::prelude::{client, server};
+use serde::de::DeserializeOwned;
 use lightyear::utils::avian3d::{position, rotation};
 use lightyear::utils::bevy::TransformLinearInterpolation;
 use serde::{Deserialize, Serialize};
 use crate::{NetworkSide};
-use crate::plugins::combatant::{CombatantMarker, CombatantType};
+use crate::plugins::combatant::{CharacterControllerSettings, CombatantMarker, CombatantOwner, CombatantType, CombatantsList, JumpTimers, Stamina, Team};
+use crate::plugins::diagnostics::ChannelStats;
+use crate::plugins::health::{Health, HitResult};
+use crate::plugins::knockback::Knockback;
+use crate::plugins::netstate::CombatantNetState;
+use crate::plugins::platforms::MovingPlatform;
+use crate::plugins::projectile::Projectile;
 use crate::plugins::statesmachine::{CurrentStates};
 
 pub struct ProtocolPlugin {
     pub predict_all: bool,
+    pub compact_combatant_state: bool,
     pub network_side: NetworkSide
 }
 
 pub const REPLICATION_GROUP: ReplicationGroup = ReplicationGroup::new_id(1);
+pub const MAX_CHAT_LENGTH: usize = 200;
 
 #[derive(Component, Serialize, Deserialize, Clone, Debug, PartialEq)]
 pub struct FloorMarker;
 
+#[derive(Channel)]
+pub struct GameplayChannel;
+
+#[derive(Channel)]
+pub struct CosmeticChannel;
+
+#[derive(Channel)]
+pub struct ChatChannel;
+
+#[derive(Component, Clone, Debug)]
+pub struct PrePredictedTimeout{
+    pub spawn_tick: u16,
+    pub timeout_ticks: u16
+}
+
+#[derive(Event, Clone, Debug)]
+pub struct PrePredictedSpawned<C: Component>{
+    pub entity: Entity,
+    pub client_id: ClientId,
+    marker: PhantomData<C>
+}
+
+pub trait AppPrePredictedExt {
+    fn register_pre_predicted<C: Component + Serialize + DeserializeOwned + Clone + PartialEq + Debug>(&mut self, network_side: &NetworkSide);
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct HitResultMessage{
+    pub target: Entity,
+    pub attacker: Option<Entity>,
+    pub result: HitResult,
+    pub amount: f32
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct KillNoticeMessage{
+    pub victim: Entity,
+    pub killer: Option<Entity>,
+    pub tick: u16
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq, Reflect)]
+pub enum CosmeticEvent{
+    Footstep,
+    Dash,
+    Land
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct CosmeticEventMessage{
+    pub source: Option<Entity>,
+    pub event: CosmeticEvent,
+    pub position: Vec3
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ChatTarget{
+    All,
+    Team,
+    Whisper(ClientId),
+    Proximity
+}
+
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Eq, Reflect)]
+pub enum ChatRejection{
+    Empty,
+    TooLong,
+    RateLimited,
+    Muted,
+    UnknownRecipient,
+    NoTeam,
+    NoCombatant
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct ChatRequestMessage{
+    pub target: ChatTarget,
+    pub text: String
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct ChatMessage{
+    pub sender: ClientId,
+    pub target: ChatTarget,
+    pub text: String
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct ChatRejectedMessage{
+    pub reason: ChatRejection
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct ChatMuteMessage{
+    pub client_id: ClientId,
+    pub muted: bool
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct ChatMuteConfirmedMessage{
+    pub client_id: ClientId,
+    pub muted: bool
+}
+
+#[derive(SystemParam)]
+pub struct ServerMessages<'w, 's>{
+    connection_manager: ResMut<'w, server::ConnectionManager>,
+    room_manager: Res<'w, RoomManager>,
+    combatants_list: Res<'w, CombatantsList>,
+    channel_stats: ResMut<'w, ChannelStats>,
+    team_query: Query<'w, 's, &'static Team>,
+}
+
+#[derive(SystemParam)]
+pub struct ClientMessages<'w>{
+    connection_manager: ResMut<'w, client::ConnectionManager>,
+    channel_stats: ResMut<'w, ChannelStats>,
+}
+
 #[derive(Clone, Copy, PartialEq, Eq, Hash, Debug, Reflect, Serialize, Deserialize)]
 pub enum CharacterAction {
     Move,
-    Jump
+    Jump,
+    Dash,
+    Block,
+    Attack,
+    Sprint,
+    Crouch,
+    Fire
 }
 
 impl Actionlike for CharacterAction {
     fn input_control_kind(&self) -> InputControlKind {
         match self {
             Self::Move => InputControlKind::DualAxis,
-            Self::Jump => InputControlKind::Button
+            Self::Jump => InputControlKind::Button,
+            Self::Dash => InputControlKind::Button,
+            Self::Block => InputControlKind::Button,
+            Self::Attack => InputControlKind::Button,
+            Self::Sprint => InputControlKind::Button,
+            Self::Crouch => InputControlKind::Button,
+            Self::Fire => InputControlKind::Button
+        }
+    }
+}
+
+impl MapEntities for HitResultMessage {
+    fn map_entities<M: EntityMapper>(&mut self, entity_mapper: &mut M) {
+        self.target = entity_mapper.map_entity(self.target);
+        self.attacker = self.attacker.map(|attacker| entity_mapper.map_entity(attacker));
+    }
+}
+
+impl MapEntities for KillNoticeMessage {
+    fn map_entities<M: EntityMapper>(&mut self, entity_mapper: &mut M) {
+        self.victim = entity_mapper.map_entity(self.victim);
+        self.killer = self.killer.map(|killer| entity_mapper.map_entity(killer));
+    }
+}
+
+impl MapEntities for CosmeticEventMessage {
+    fn map_entities<M: EntityMapper>(&mut self, entity_mapper: &mut M) {
+        self.source = self.source.map(|source| entity_mapper.map_entity(source));
+    }
+}
+
+impl ServerMessages<'_, '_> {
+    pub fn send_to_client<C: Channel, M: Message>(&mut self, client_id: ClientId, message: &mut M){
+        self.channel_stats.record_out::<C, M>(message, 1);
+
+        let _ = self.connection_manager.send_message_to_target::<C, M>(message, NetworkTarget::Single(client_id));
+    }
+
+    pub fn send_to_clients<C: Channel, M: Message>(&mut self, client_ids: Vec<ClientId>, message: &mut M){
+        if client_ids.is_empty() {
+            return;
+        }
+
+        self.channel_stats.record_out::<C, M>(message, client_ids.len());
+
+        let _ = self.connection_manager.send_message_to_target::<C, M>(message, NetworkTarget::Only(client_ids));
+    }
+
+    pub fn send_to_team<C: Channel, M: Message>(&mut self, team: Team, message: &mut M){
+        let client_ids = self.team_clients(team);
+
+        self.send_to_clients::<C, M>(client_ids, message);
+    }
+
+    pub fn send_to_room<C: Channel, M: Message>(&mut self, room_id: RoomId, message: &mut M){
+        let recipients = self.room_manager.get_room(room_id).map_or(0, |room| room.clients.len());
+
+        self.channel_stats.record_out::<C, M>(message, recipients);
+
+        let _ = self.connection_manager.send_message_to_room::<C, M>(message, room_id, &self.room_manager);
+    }
+
+    pub fn send_to_all<C: Channel, M: Message>(&mut self, message: &mut M){
+        let recipients = self.connection_manager.connected_clients().count();
+
+        self.channel_stats.record_out::<C, M>(message, recipients);
+
+        let _ = self.connection_manager.send_message_to_target::<C, M>(message, NetworkTarget::All);
+    }
+
+    pub fn send_to_all_except<C: Channel, M: Message>(&mut self, client_id: ClientId, message: &mut M){
+        let recipients = self.connection_manager.connected_clients().filter(|connected| *connected != client_id).count();
+
+        self.channel_stats.record_out::<C, M>(message, recipients);
+
+        let _ = self.connection_manager.send_message_to_target::<C, M>(message, NetworkTarget::AllExceptSingle(client_id));
+    }
+
+    pub fn clients(&self) -> Vec<ClientId>{
+        self.connection_manager.connected_clients().collect()
+    }
+
+    pub fn client_combatant(&self, client_id: ClientId) -> Option<Entity>{
+        self.combatants_list.0.iter()
+            .find(|(_, owner)| **owner == Some(client_id))
+            .map(|(entity, _)| *entity)
+    }
+
+    pub fn team_clients(&self, team: Team) -> Vec<ClientId>{
+        self.combatants_list.0.iter()
+            .filter(|(entity, _)| self.team_query.get(**entity).is_ok_and(|combatant_team| *combatant_team == team))
+            .filter_map(|(_, client_id)| *client_id)
+            .collect()
+    }
+}
+
+impl ClientMessages<'_> {
+    pub fn send_to_server<C: Channel, M: Message>(&mut self, message: &mut M){
+        self.channel_stats.record_out::<C, M>(message, 1);
+
+        let _ = self.connection_manager.send_message::<C, M>(message);
+    }
+}
+
+impl AppPrePredictedExt for App {
+    fn register_pre_predicted<C: Component + Serialize + DeserializeOwned + Clone + PartialEq + Debug>(&mut self, network_side: &NetworkSide){
+        self.register_component::<C>(ChannelDirection::Bidirectional)
+            .add_prediction(ComponentSyncMode::Once)
+            .add_interpolation(ComponentSyncMode::Once);
+
+        if *network_side == NetworkSide::Server {
+            self.add_event::<PrePredictedSpawned<C>>();
+            self.add_systems(PreUpdate,detect_pre_predicted_spawns::<C>);
+        }
+    }
+}
+
+pub fn pre_predicted_spawn(tick: Tick, timeout_ticks: u16) -> (PrePredicted, client::Replicate, PrePredictedTimeout){
+    (
+        PrePredicted::default(),
+        client::Replicate::default(),
+        PrePredictedTimeout{
+            spawn_tick: tick.0,
+            timeout_ticks
+        }
+    )
+}
+
+pub fn accept_pre_predicted(commands: &mut Commands, entity: Entity, client_id: ClientId){
+    commands.entity(entity).insert(server::Replicate{
+        controlled_by: ControlledBy {
+            target: NetworkTarget::Single(client_id),
+            ..default()
+        },
+        group: ReplicationGroup::default(),
+        sync: SyncTarget {
+            prediction: NetworkTarget::Single(client_id),
+            interpolation: NetworkTarget::AllExceptSingle(client_id),
+            ..default()
+        },
+        visibility: VisibilityMode::InterestManagement,
+        ..default()
+    });
+}
+
+pub fn reject_pre_predicted(commands: &mut Commands, entity: Entity){
+    commands.entity(entity).despawn();
+}
+
+fn detect_pre_predicted_spawns<C: Component>(
+    spawned_query: Query<(Entity, &Replicated), (Added<C>, With<PrePredicted>)>,
+    mut spawned_events: EventWriter<PrePredictedSpawned<C>>,
+){
+    for (entity, replicated) in spawned_query.iter() {
+        let Some(client_id) = replicated.from else {continue};
+
+        spawned_events.send(PrePredictedSpawned{
+            entity,
+            client_id,
+            marker: PhantomData
+        });
+    }
+}
+
+fn count_client_messages<C: Channel, M: Message>(
+    mut received_messages: EventReader<ClientReceiveMessage<M>>,
+    mut channel_stats: ResMut<ChannelStats>,
+){
+    for event in received_messages.read() {
+        channel_stats.record_in::<C, M>(event.message());
+    }
+}
+
+fn count_server_messages<C: Channel, M: Message>(
+    mut received_messages: EventReader<ServerReceiveMessage<M>>,
+    mut channel_stats: ResMut<ChannelStats>,
+){
+    for event in received_messages.read() {
+        channel_stats.record_in::<C, M>(event.message());
+    }
+}
+
+fn despawn_unconfirmed_pre_predicted(
+    mut commands: Commands,
+    pre_predicted_query: Query<(Entity, &PrePredictedTimeout, Option<&Predicted>)>,
+    tick_manager: Res<TickManager>,
+){
+    let tick = tick_manager.tick();
+
+    for (entity, timeout, predicted) in pre_predicted_query.iter() {
+        if predicted.is_some_and(|predicted| predicted.confirmed_entity.is_some()) {
+            commands.entity(entity).remove::<PrePredictedTimeout>();
+            continue;
+        }
+
+        if (tick - Tick(timeout.spawn_tick)) >= timeout.timeout_ticks as i16 {
+            commands.entity(entity).despawn();
         }
     }
 }
 
 impl Plugin for ProtocolPlugin{
     fn build(&self, app: &mut App) {
+        app.add_channel::<GameplayChannel>(ChannelSettings {
+            mode: ChannelMode::OrderedReliable(ReliableSettings::default()),
+            ..default()
+        });
+
+        app.add_channel::<CosmeticChannel>(ChannelSettings {
+            mode: ChannelMode::UnorderedUnreliable,
+            ..default()
+        });
+
+        app.add_channel::<ChatChannel>(ChannelSettings {
+            mode: ChannelMode::OrderedReliable(ReliableSettings::default()),
+            ..default()
+        });
+
+        app.register_message::<HitResultMessage>(ChannelDirection::ServerToClient)
+            .add_map_entities();
+
+        app.register_message::<KillNoticeMessage>(ChannelDirection::ServerToClient)
+            .add_map_entities();
+
+        app.register_message::<CosmeticEventMessage>(ChannelDirection::Bidirectional)
+            .add_map_entities();
+
+        app.register_message::<ChatRequestMessage>(ChannelDirection::ClientToServer);
+        app.register_message::<ChatMuteMessage>(ChannelDirection::ClientToServer);
+        app.register_message::<ChatMessage>(ChannelDirection::ServerToClient);
+        app.register_message::<ChatRejectedMessage>(ChannelDirection::ServerToClient);
+        app.register_message::<ChatMuteConfirmedMessage>(ChannelDirection::ServerToClient);
+
+        let mut channel_stats = ChannelStats::default();
+
+        channel_stats.register::<GameplayChannel>();
+        channel_stats.register::<CosmeticChannel>();
+        channel_stats.register::<ChatChannel>();
+        app.insert_resource(channel_stats);
+
+        if self.network_side == NetworkSide::Client {
+            app.add_systems(Update,(
+                count_client_messages::<GameplayChannel,HitResultMessage>,
+                count_client_messages::<GameplayChannel,KillNoticeMessage>,
+                count_client_messages::<CosmeticChannel,CosmeticEventMessage>,
+                count_client_messages::<ChatChannel,ChatMessage>,
+                count_client_messages::<ChatChannel,ChatRejectedMessage>,
+                count_client_messages::<ChatChannel,ChatMuteConfirmedMessage>,
+            ));
+        }else {
+            app.add_systems(Update,(
+                count_server_messages::<CosmeticChannel,CosmeticEventMessage>,
+                count_server_messages::<ChatChannel,ChatRequestMessage>,
+                count_server_messages::<ChatChannel,ChatMuteMessage>,
+            ));
+        }
+
         app.add_plugins(LeafwingInputPlugin::<CharacterAction> {
             config: InputConfig::<CharacterAction> {
                 rebroadcast_inputs: self.predict_all,
@@ -49,32 +440,74 @@ impl Plugin for ProtocolPlugin{
             .add_prediction(ComponentSyncMode::Once);
 
         app.register_component::<CombatantMarker>(ChannelDirection::ServerToClient)
-            .add_prediction(ComponentSyncMode::Once);
+            .add_prediction(ComponentSyncMode::Once)
+            .add_interpolation(ComponentSyncMode::Once);
 
         app.register_component::<CombatantType>(ChannelDirection::ServerToClient)
-            .add_prediction(ComponentSyncMode::Once);
+            .add_prediction(ComponentSyncMode::Once)
+            .add_interpolation(ComponentSyncMode::Once);
 
-        app.register_component::<GravityScale>(ChannelDirection::ServerToClient)
-            .add_prediction(ComponentSyncMode::Once);
+        app.register_component::<CombatantOwner>(ChannelDirection::ServerToClient)
+            .add_prediction(ComponentSyncMode::Once)
+            .add_interpolation(ComponentSyncMode::Once);
 
-        app.register_component::<CurrentStates>(ChannelDirection::ServerToClient)
-            .add_prediction(ComponentSyncMode::Simple);
+        app.register_component::<Team>(ChannelDirection::ServerToClient)
+            .add_prediction(ComponentSyncMode::Simple)
+            .add_interpolation(ComponentSyncMode::Simple);
 
-        app.register_component::<LinearVelocity>(ChannelDirection::ServerToClient)
+        app.register_component::<MovingPlatform>(ChannelDirection::ServerToClient)
             .add_prediction(ComponentSyncMode::Full);
 
-        app.register_component::<AngularVelocity>(ChannelDirection::ServerToClient)
+        app.register_component::<CharacterControllerSettings>(ChannelDirection::ServerToClient)
+            .add_prediction(ComponentSyncMode::Simple);
+
+        app.register_pre_predicted::<Projectile>(&self.network_side);
+
+        if self.network_side == NetworkSide::Client {
+            app.add_systems(FixedUpdate,despawn_unconfirmed_pre_predicted);
+        }
+
+        app.register_component::<CurrentStates>(ChannelDirection::ServerToClient)
             .add_prediction(ComponentSyncMode::Full);
 
-        app.register_component::<ExternalForce>(ChannelDirection::ServerToClient)
+        app.register_component::<Health>(ChannelDirection::ServerToClient)
+            .add_prediction(ComponentSyncMode::Simple)
+            .add_interpolation(ComponentSyncMode::Simple);
+
+        app.register_component::<Knockback>(ChannelDirection::ServerToClient)
             .add_prediction(ComponentSyncMode::Full);
 
-        app.register_component::<ExternalImpulse>(ChannelDirection::ServerToClient)
+        app.register_component::<Stamina>(ChannelDirection::ServerToClient)
             .add_prediction(ComponentSyncMode::Full);
 
-        app.register_component::<ComputedMass>(ChannelDirection::ServerToClient)
+        app.register_component::<JumpTimers>(ChannelDirection::ServerToClient)
             .add_prediction(ComponentSyncMode::Full);
 
+        if self.compact_combatant_state {
+            app.register_component::<CombatantNetState>(ChannelDirection::ServerToClient)
+                .add_prediction(ComponentSyncMode::Full)
+                .add_interpolation_fn(CombatantNetState::lerp)
+                .add_interpolation(ComponentSyncMode::Full);
+        }else {
+            app.register_component::<GravityScale>(ChannelDirection::ServerToClient)
+                .add_prediction(ComponentSyncMode::Once);
+
+            app.register_component::<LinearVelocity>(ChannelDirection::ServerToClient)
+                .add_prediction(ComponentSyncMode::Full);
+
+            app.register_component::<AngularVelocity>(ChannelDirection::ServerToClient)
+                .add_prediction(ComponentSyncMode::Full);
+
+            app.register_component::<ExternalForce>(ChannelDirection::ServerToClient)
+                .add_prediction(ComponentSyncMode::Full);
+
+            app.register_component::<ExternalImpulse>(ChannelDirection::ServerToClient)
+                .add_prediction(ComponentSyncMode::Full);
+
+            app.register_component::<ComputedMass>(ChannelDirection::ServerToClient)
+                .add_prediction(ComponentSyncMode::Full);
+        }
+
         app.register_component::<Position>(ChannelDirection::ServerToClient)
             .add_prediction(ComponentSyncMode::Full)
             .add_interpolation_fn(position::lerp)
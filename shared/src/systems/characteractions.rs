@@ -1,5 +1,7 @@
 use bevy::math::Vec3;
 use bevy::prelude::{default, Vec2};
+use lightyear::prelude::Tick;
+use crate::plugins::combatant::{CharacterControllerSettings, DashSettings, JumpTimers, Stamina};
 use crate::plugins::statesmachine::{CurrentStates, StateInfos, States, StatesValues};
 
 pub fn move_action(
@@ -7,14 +9,161 @@ pub fn move_action(
     current_states: &mut CurrentStates
 ){
     if move_dir.y != 0.0 || move_dir.x != 0.0 {
+        let walking_values = Some(StatesValues::Walking(Vec3::new(-move_dir.x,0.0,move_dir.y)));
+
+        if let Some(state_infos) = current_states.0.get_mut(&States::Walking) {
+            if state_infos.values != walking_values {
+                state_infos.values = walking_values;
+            }
+
+            return;
+        }
+
         current_states.transition(&States::Walking,StateInfos{
-            values: Some(StatesValues::Walking(Vec3::new(-move_dir.x,0.0,move_dir.y))),
+            values: walking_values,
+            ..default()
+        });
+    }else {
+        current_states.stop(&States::Walking);
+        current_states.transition(&States::Idle,StateInfos{
+            values: None,
+            ..default()
+        });
+    }
+}
+
+pub fn jump_action(
+    jump_pressed: bool,
+    grounded: bool,
+    tick: Tick,
+    settings: &CharacterControllerSettings,
+    jump_timers: &mut JumpTimers,
+    current_states: &mut CurrentStates
+){
+    let is_jumping = current_states.0.contains_key(&States::Jumping);
+
+    if grounded && !is_jumping {
+        jump_timers.last_grounded_tick = Some(tick.0);
+    }
+
+    if jump_pressed {
+        jump_timers.jump_pressed_tick = Some(tick.0);
+    }
+
+    let Some(jump_pressed_tick) = jump_timers.jump_pressed_tick else {return};
+
+    if (tick - Tick(jump_pressed_tick)) > settings.jump_buffer_ticks as i16 {
+        jump_timers.jump_pressed_tick = None;
+        return;
+    }
+
+    let can_jump = jump_timers.last_grounded_tick
+        .is_some_and(|last_grounded_tick| (tick - Tick(last_grounded_tick)) <= settings.coyote_ticks as i16);
+
+    if is_jumping || !can_jump || !current_states.can_transition(&States::Jumping) {
+        return;
+    }
+
+    current_states.transition(&States::Jumping,StateInfos{
+        values: Some(StatesValues::Jumping(false)),
+        ..default()
+    });
+    jump_timers.last_grounded_tick = None;
+    jump_timers.jump_pressed_tick = None;
+}
+
+pub fn sprint_action(
+    sprint_pressed: bool,
+    stamina: &Stamina,
+    current_states: &mut CurrentStates
+){
+    if sprint_pressed && stamina.current > 0.0 && current_states.0.contains_key(&States::Walking) {
+        current_states.transition(&States::Sprinting,StateInfos{
+            values: None,
             ..default()
         });
     }else {
+        current_states.stop(&States::Sprinting);
+    }
+}
+
+pub fn crouch_action(
+    crouch_pressed: bool,
+    crouch_just_pressed: bool,
+    crouch_toggle: bool,
+    current_states: &mut CurrentStates
+){
+    let crouch_requested = match current_states.0.get(&States::Crouching).and_then(|state_infos| state_infos.values.clone()) {
+        Some(StatesValues::Crouching(requested)) => Some(requested),
+        _ => None
+    };
+    let requested = if !crouch_toggle {crouch_pressed} else if crouch_just_pressed {!crouch_requested.unwrap_or(false)} else {crouch_requested.unwrap_or(false)};
+
+    match crouch_requested {
+        None if requested => {
+            current_states.transition(&States::Crouching,StateInfos{
+                values: Some(StatesValues::Crouching(true)),
+                ..default()
+            });
+        },
+        Some(previous) if previous != requested => {
+            if let Some(state_infos) = current_states.0.get_mut(&States::Crouching) {
+                state_infos.values = Some(StatesValues::Crouching(requested));
+            }
+        },
+        _ => {}
+    }
+}
+
+pub fn dash_action(
+    move_dir: Vec2,
+    tick: Tick,
+    current_states: &mut CurrentStates
+) -> bool{
+    if (move_dir.y == 0.0 && move_dir.x == 0.0) || !current_states.can_transition(&States::Dashing) {
+        return false;
+    }
+
+    current_states.transition(&States::Dashing,StateInfos{
+        start: Some(tick.0),
+        values: Some(StatesValues::Dashing(Vec3::new(-move_dir.x,0.0,move_dir.y).normalize(),tick.0)),
+        ..default()
+    });
+
+    true
+}
+
+pub fn block_action(
+    block_pressed: bool,
+    tick: Tick,
+    current_states: &mut CurrentStates
+){
+    if block_pressed {
+        current_states.transition(&States::Blocking,StateInfos{
+            start: Some(tick.0),
+            values: Some(StatesValues::Blocking(tick.0)),
+            ..default()
+        });
+    }else if current_states.stop(&States::Blocking).is_some() {
         current_states.transition(&States::Idle,StateInfos{
             values: None,
             ..default()
         });
     }
+}
+
+pub fn can_attack(current_states: &CurrentStates) -> bool{
+    ![States::Died,States::Staggered,States::Blocking,States::Dashing].iter().any(|state| current_states.0.contains_key(state))
+}
+
+pub fn is_invulnerable(
+    current_states: &CurrentStates,
+    tick: Tick,
+    dash_settings: &DashSettings
+) -> bool{
+    if let Some(StatesValues::Dashing(_, start_tick)) = current_states.0.get(&States::Dashing).and_then(|state_infos| state_infos.values.as_ref()) {
+        return (tick - Tick(*start_tick)) < dash_settings.invulnerable_ticks as i16;
+    }
+
+    false
 }
\ No newline at end of file
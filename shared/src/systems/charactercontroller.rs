@@ -1,22 +1,88 @@
-use avian3d::prelude::{Collider, ComputedMass, ExternalForce, Gravity, LayerMask, LinearVelocity, ShapeCastConfig, ShapeHitData, SpatialQuery, SpatialQueryFilter};
+use avian3d::prelude::{AngularVelocity, Collider, ComputedMass, ExternalForce, Gravity, LayerMask, LinearVelocity, Position, RigidBody, Rotation, ShapeCastConfig, ShapeHitData, SpatialQuery, SpatialQueryFilter};
 use bevy::ecs::entity::EntityHashSet;
 use bevy::math::{vec3, Dir3};
-use bevy::prelude::{Entity, Fixed, Quat, Query, Res, Time, Transform, Vec3, With};
+use bevy::prelude::{default, DetectChangesMut, Entity, EventWriter, Fixed, Quat, Query, Res, Time, Transform, Vec3, With, Without};
+use lightyear::prelude::client::Rollback;
+use lightyear::prelude::{Tick, TickManager};
 use crate::{GameMask, InteractNetworkAble};
-use crate::plugins::combatant::{CharacterController};
-use crate::plugins::statesmachine::{CurrentStates, States, StatesValues};
+use leafwing_input_manager::prelude::ActionState;
+use lightyear::inputs::leafwing::input_buffer::InputBuffer;
+use crate::plugins::knockback::{is_stunned, Knockback};
+use crate::plugins::combatant::{CharacterController, CharacterControllerSettings, CombatSettings, CombatantMarker, DashSettings, DashStarted, JumpTimers, Stamina};
+use crate::plugins::shared::current_tick;
+use crate::plugins::statesmachine::{CurrentStates, StateInfos, States, StatesValues};
+use crate::protocol::CharacterAction;
+use crate::systems::characteractions::{dash_action, jump_action};
 
-const FLOAT_DISTANCE: f32 = 0.1;
+const STEP_CHECK_DISTANCE: f32 = 0.2;
+
+pub fn collider_half_extents(collider: &Collider) -> Vec3{
+    let aabb = collider.aabb(Vec3::ZERO, Quat::IDENTITY);
+
+    (aabb.max - aabb.min) / 2.0
+}
+
+pub fn crouch_ratio(settings: &CharacterControllerSettings) -> f32{
+    (settings.crouch_height / settings.stand_height).clamp(0.1, 1.0)
+}
+
+pub fn collider_half_height(collider: &Collider, settings: &CharacterControllerSettings, crouched: bool) -> f32{
+    let height_ratio = if crouched {crouch_ratio(settings)} else {1.0};
+
+    settings.foot_offset
+        .map(|foot_offset| foot_offset * height_ratio)
+        .unwrap_or_else(|| collider_half_extents(collider).y)
+}
+
+fn resize_collider_height(collider: &mut Collider, height: f32){
+    if let Some(capsule_collider) = collider.shape().as_capsule() {
+        let radius = capsule_collider.radius;
+
+        *collider = Collider::capsule(radius, (height - radius * 2.0).max(0.0));
+        return;
+    }
+
+    let current_height = collider_half_extents(collider).y * 2.0;
+    let scale = collider.scale();
+
+    if current_height > f32::EPSILON {
+        collider.set_scale(scale.with_y(scale.y * height / current_height), 8);
+    }
+}
+
+pub fn is_walkable_normal(normal: Vec3, slope_limit: f32) -> bool{
+    normal.angle_between(Vec3::Y) <= slope_limit.to_radians()
+}
+
+fn move_towards(current: Vec3, target: Vec3, max_delta: f32) -> Vec3{
+    let difference = target - current;
+    let distance = difference.length();
+
+    if distance <= max_delta || distance <= f32::EPSILON {
+        target
+    }else {
+        current + difference / distance * max_delta
+    }
+}
+
+pub fn ground_mask(settings: &CharacterControllerSettings) -> LayerMask{
+    if settings.combatants_as_ground {
+        GameMask::environment() | GameMask::Combatant
+    }else {
+        GameMask::environment()
+    }
+}
 
 pub fn find_ground(
     entity: Entity,
     query: &SpatialQuery,
     translation: &Vec3,
     rotation: &Quat,
-    collider: &Collider
+    collider: &Collider,
+    half_height: f32,
+    mask: LayerMask
 )-> Option<ShapeHitData> {
-    let capsule_collider= if let Some(capsule) = collider.shape().as_capsule() {capsule} else {return None};
-    let height = capsule_collider.height() + (capsule_collider.radius * 2.0);
+    let height = half_height * 2.0;
     let mut ignore_list = EntityHashSet::default();
 
     ignore_list.insert(entity);
@@ -27,81 +93,461 @@ pub fn find_ground(
         compute_contact_on_penetration: true,
         ignore_origin_penetration: true
     },&SpatialQueryFilter{
-        mask: LayerMask::from(GameMask::Default),
+        mask,
         excluded_entities: ignore_list,
     })
 }
 
 pub fn check_is_grounded(
     query: SpatialQuery,
-    mut character_query: Query<(Entity, &mut CharacterController, &Collider, &Transform), (With<InteractNetworkAble>, With<CharacterController>)>
+    mut character_query: Query<(Entity, &mut CharacterController, &CharacterControllerSettings, &Collider, &Transform), (With<InteractNetworkAble>, With<CharacterController>)>,
+    ground_query: Query<(&RigidBody, &Position, Option<&LinearVelocity>, Option<&AngularVelocity>)>,
+){
+    for (entity, mut character_controller, settings, collider, transform) in character_query.iter_mut(){
+        let shape_hit_data = find_ground(entity,&query,&transform.translation,&transform.rotation,&collider,collider_half_height(collider, settings, character_controller.crouched),ground_mask(settings));
+
+        character_controller.ground_velocity = shape_hit_data.as_ref()
+            .and_then(|hit| ground_query.get(hit.entity).ok().map(|ground| (hit.point1, ground)))
+            .filter(|(_, (rigid_body, _, _, _))| rigid_body.is_kinematic())
+            .map(|(contact_point, (_, ground_position, linear_velocity, angular_velocity))| {
+                let linear_velocity = linear_velocity.map(|velocity| velocity.0).unwrap_or(Vec3::ZERO);
+                let angular_velocity = angular_velocity.map(|velocity| velocity.0).unwrap_or(Vec3::ZERO);
+
+                linear_velocity + angular_velocity.cross(contact_point - ground_position.0)
+            })
+            .unwrap_or(Vec3::ZERO);
+        character_controller.ground_normal = shape_hit_data.as_ref().map(|hit| hit.normal1).unwrap_or(Vec3::Y);
+        character_controller.grounded = shape_hit_data.is_some() && is_walkable_normal(character_controller.ground_normal, settings.slope_limit);
+        character_controller.shape_hit_data = shape_hit_data;
+    }
+}
+
+pub fn character_jump_input(
+    mut character_query: Query<(&ActionState<CharacterAction>, &CharacterController, &CharacterControllerSettings, Option<&Knockback>, &mut JumpTimers, &mut CurrentStates), (With<CharacterController>, With<InteractNetworkAble>)>,
+    tick_manager: Res<TickManager>,
+    rollback: Option<Res<Rollback>>,
+){
+    let tick = current_tick(&tick_manager, rollback.as_deref());
+
+    for (action_state, character_controller, settings, knockback, mut jump_timers, mut current_states) in character_query.iter_mut(){
+        let mut next_jump_timers = jump_timers.clone();
+        let jump_pressed = action_state.just_pressed(&CharacterAction::Jump) && !is_stunned(knockback, tick);
+
+        jump_action(jump_pressed, character_controller.grounded, tick, settings, &mut next_jump_timers, &mut current_states);
+        jump_timers.set_if_neq(next_jump_timers);
+    }
+}
+
+pub fn character_dash_input(
+    mut character_query: Query<(Entity, &ActionState<CharacterAction>, Option<&InputBuffer<CharacterAction>>, Option<&Knockback>, &Position, &mut CurrentStates), (With<CharacterController>, With<InteractNetworkAble>)>,
+    mut dash_started_events: EventWriter<DashStarted>,
+    tick_manager: Res<TickManager>,
+    rollback: Option<Res<Rollback>>,
+){
+    let tick = current_tick(&tick_manager, rollback.as_deref());
+    let is_rollback = rollback.as_ref().is_some_and(|rollback| rollback.is_rollback());
+
+    for (entity, action_state, input_buffer, knockback, position, mut current_states) in character_query.iter_mut(){
+        if is_stunned(knockback, tick) {
+            continue;
+        }
+
+        let (dash_pressed, move_dir) = match input_buffer.and_then(|input_buffer| input_buffer.get(tick)) {
+            Some(buffered_action_state) => {
+                let previously_pressed = input_buffer
+                    .and_then(|input_buffer| input_buffer.get(Tick(tick.0.wrapping_sub(1))))
+                    .is_some_and(|previous_action_state| previous_action_state.pressed(&CharacterAction::Dash));
+
+                (buffered_action_state.pressed(&CharacterAction::Dash) && !previously_pressed, buffered_action_state.axis_pair(&CharacterAction::Move))
+            },
+            None => (action_state.just_pressed(&CharacterAction::Dash), action_state.axis_pair(&CharacterAction::Move))
+        };
+
+        if dash_pressed && dash_action(move_dir.clamp_length_max(1.0), tick, &mut current_states) && !is_rollback {
+            dash_started_events.send(DashStarted{
+                entity,
+                position: position.0
+            });
+        }
+    }
+}
+
+pub fn character_crouch(
+    query: SpatialQuery,
+    mut character_query: Query<(Entity, &mut CharacterController, &CharacterControllerSettings, &mut CurrentStates, &mut Collider, &mut Position, &Rotation), (With<InteractNetworkAble>, With<CharacterController>)>
 ){
-    for (entity, mut character_controller, collider, transform) in character_query.iter_mut(){
-        character_controller.shape_hit_data = find_ground(entity,&query,&transform.translation,&transform.rotation,&collider);
-        character_controller.grounded = if character_controller.shape_hit_data.is_some() {true} else {false};
+    for (entity, mut character_controller, settings, mut current_states, mut collider, mut position, rotation) in character_query.iter_mut(){
+        let current_height = collider_half_extents(&collider).y * 2.0;
+        let crouch_ratio = crouch_ratio(settings);
+        let standing_height = if character_controller.crouched {current_height / crouch_ratio} else {current_height};
+
+        if let Some(StatesValues::Crouching(false)) = current_states.0.get(&States::Crouching).and_then(|state_infos| state_infos.values.clone()) {
+            let headroom = standing_height - current_height;
+            let blocked = headroom > 0.0 && query.cast_shape(&collider, position.0, rotation.0, Dir3::Y, &ShapeCastConfig{
+                max_distance: headroom,
+                ignore_origin_penetration: true,
+                ..default()
+            }, &SpatialQueryFilter::from_mask(GameMask::environment()).with_excluded_entities([entity])).is_some();
+
+            if !blocked {
+                current_states.stop(&States::Crouching);
+
+                if !current_states.0.contains_key(&States::Walking) {
+                    current_states.transition(&States::Idle,StateInfos{
+                        values: None,
+                        ..default()
+                    });
+                }
+            }
+        }
+
+        let crouching = current_states.0.contains_key(&States::Crouching);
+
+        if crouching == character_controller.crouched {
+            continue;
+        }
+
+        let target_height = if crouching {standing_height * crouch_ratio} else {standing_height};
+
+        resize_collider_height(&mut collider, target_height);
+        position.0.y += (target_height - current_height) / 2.0;
+        character_controller.crouched = crouching;
     }
 }
 
 pub fn adjust_collider_float(
-    mut character_query: Query<(&CharacterController, &Collider, &Transform, &mut ExternalForce, &mut LinearVelocity, &ComputedMass), (With<InteractNetworkAble>,With<CharacterController>)>
+    mut character_query: Query<(&CharacterController, &CharacterControllerSettings, &CurrentStates, &Collider, &Transform, &mut ExternalForce, &mut LinearVelocity, &ComputedMass), (With<InteractNetworkAble>,With<CharacterController>)>
 ){
-    for (character_controller, collider, transform, mut external_forces, mut linear_velocity, computed_mass) in character_query.iter_mut(){
-        let capsule_collider= if let Some(capsule) = collider.shape().as_capsule() {capsule} else {continue};
-        let height: f32 = capsule_collider.height() + (capsule_collider.radius * 2.0);
-        let half_height = height / 2.0;
+    for (character_controller, settings, current_states, collider, transform, mut external_forces, mut linear_velocity, computed_mass) in character_query.iter_mut(){
+        let half_height = collider_half_height(collider, settings, character_controller.crouched);
         let current_translation = transform.translation;
         let mass_value = computed_mass.value();
+        let is_rising_from_jump = current_states.0.contains_key(&States::Jumping) && linear_velocity.y > 0.0;
+
+        if !character_controller.grounded || is_rising_from_jump {
+            if external_forces.y != 0.0 {
+                external_forces.set_force(Vec3::ZERO);
+            }
+
+            continue;
+        }
 
         if let Some(ref shape_hit_data) = character_controller.shape_hit_data{
             let ground_point = shape_hit_data.point1;
-            let float_point = (ground_point.y + half_height) + FLOAT_DISTANCE;
+            let float_point = (ground_point.y + half_height) + settings.float_height;
             let stand_difference = current_translation.y - float_point;
 
-            if stand_difference.abs() <= 0.005 {
-                if linear_velocity.y != 0.0 {
-                    linear_velocity.y = 0.0;
+            let ground_vertical_velocity = character_controller.ground_velocity.y;
+            let relative_vertical_velocity = linear_velocity.y - ground_vertical_velocity;
+
+            if stand_difference.abs() <= settings.float_dead_zone {
+                if linear_velocity.y != ground_vertical_velocity {
+                    linear_velocity.y = ground_vertical_velocity;
                 }
 
                 if external_forces.y != 0.0 {
-                    external_forces.y = 0.0;
+                    external_forces.set_force(Vec3::ZERO);
                 }
-            }else if stand_difference > 0.0 {
-                external_forces.apply_force(Vec3::new(0.0, stand_difference * mass_value, 0.0));
             }else {
-                external_forces.apply_force(Vec3::new(0.0, -stand_difference * mass_value, 0.0));
+                let spring_force = -stand_difference * settings.spring_stiffness - relative_vertical_velocity * settings.spring_damping;
+
+                external_forces.set_force(Vec3::new(0.0, spring_force * mass_value, 0.0));
             }
         }
     }
 }
 
+pub fn character_slide(
+    mut character_query: Query<(&CharacterController, &mut LinearVelocity), (With<CharacterController>, With<InteractNetworkAble>)>,
+    gravity: Res<Gravity>,
+    time_fixed: Res<Time<Fixed>>,
+){
+    let delta = time_fixed.delta().as_secs_f32();
+
+    for (character_controller, mut linear_velocity) in character_query.iter_mut(){
+        if character_controller.grounded || character_controller.shape_hit_data.is_none() {
+            continue;
+        }
+
+        let wall_normal = character_controller.ground_normal;
+        let slide_acceleration = gravity.0 - wall_normal * gravity.0.dot(wall_normal);
+        let into_wall = linear_velocity.0.dot(wall_normal);
+
+        linear_velocity.0 += slide_acceleration * delta;
+
+        if into_wall < 0.0 {
+            linear_velocity.0 -= wall_normal * into_wall;
+        }
+    }
+}
+
+pub fn character_step_up(
+    query: SpatialQuery,
+    mut character_query: Query<(Entity, &CharacterController, &CharacterControllerSettings, &CurrentStates, &Collider, &mut Position), (With<CharacterController>, With<InteractNetworkAble>)>
+){
+    for (entity, character_controller, settings, current_states, collider, mut position) in character_query.iter_mut(){
+        if !character_controller.grounded {
+            continue;
+        }
+
+        let Some(ref shape_hit_data) = character_controller.shape_hit_data else {continue};
+        let Some(StatesValues::Walking(walking_direction)) = current_states.0.get(&States::Walking).and_then(|state_infos| state_infos.values.clone()) else {continue};
+        let Ok(walking_direction) = Dir3::new(walking_direction.with_y(0.0)) else {continue};
+        let half_extents = collider_half_extents(collider);
+        let filter = SpatialQueryFilter::from_mask(ground_mask(settings)).with_excluded_entities([entity]);
+        let check_distance = half_extents.x.min(half_extents.z) + STEP_CHECK_DISTANCE;
+        let foot_height = shape_hit_data.point1.y;
+
+        let Some(obstacle) = query.cast_ray(position.0.with_y(foot_height + 0.05), walking_direction, check_distance, true, &filter) else {continue};
+
+        if is_walkable_normal(obstacle.normal, settings.slope_limit) {
+            continue;
+        }
+
+        let step_origin = position.0.with_y(foot_height + settings.max_step_height);
+
+        if query.cast_ray(step_origin, walking_direction, check_distance, true, &filter).is_some() {
+            continue;
+        }
+
+        let probe_origin = step_origin + walking_direction * (obstacle.distance + 0.05);
+        let Some(step_top) = query.cast_ray(probe_origin, -Dir3::Y, settings.max_step_height, true, &filter) else {continue};
+        let step_height = settings.max_step_height - step_top.distance;
+
+        if step_height > 0.0 && is_walkable_normal(step_top.normal, settings.slope_limit) {
+            position.0.y += step_height;
+        }
+    }
+}
+
 pub fn character_walk(
-    mut character_query: Query<(&mut CurrentStates, &mut LinearVelocity), (With<CharacterController>, With<InteractNetworkAble>)>
+    mut character_query: Query<(&CharacterController, &CharacterControllerSettings, &CurrentStates, Option<&Knockback>, &mut LinearVelocity), (With<CharacterController>, With<InteractNetworkAble>)>,
+    tick_manager: Res<TickManager>,
+    rollback: Option<Res<Rollback>>,
+    time_fixed: Res<Time<Fixed>>,
 ){
-    for (mut current_states, mut linear_velocity) in character_query.iter_mut(){
-        if !current_states.0.contains_key(&States::Walking){
-            return;
+    let tick = current_tick(&tick_manager, rollback.as_deref());
+    let delta = time_fixed.delta().as_secs_f32();
+
+    for (character_controller, settings, current_states, knockback, mut linear_velocity) in character_query.iter_mut(){
+        if current_states.0.contains_key(&States::Dashing) {
+            continue;
+        }
+
+        let walking_direction = match current_states.0.get(&States::Walking).and_then(|state_infos| state_infos.values.clone()) {
+            Some(StatesValues::Walking(walking_direction)) if !is_stunned(knockback, tick) => walking_direction.with_y(0.0).clamp_length_max(1.0),
+            _ => Vec3::ZERO
+        };
+        let ground_normal = character_controller.ground_normal;
+        let control = if character_controller.grounded {1.0} else {settings.air_control};
+        let speed_multiplier = if current_states.0.contains_key(&States::Sprinting) {
+            settings.sprint_multiplier
+        }else if current_states.0.contains_key(&States::Crouching) {
+            settings.crouch_multiplier
+        }else {
+            1.0
+        };
+        let mut target_velocity = walking_direction * settings.max_speed * speed_multiplier;
+        let ground_velocity = character_controller.ground_velocity;
+        let current_velocity = (linear_velocity.0 - ground_velocity).with_y(0.0);
+
+        if !character_controller.grounded && character_controller.shape_hit_data.is_some() {
+            let wall_normal = ground_normal.with_y(0.0).normalize_or_zero();
+            let into_wall = target_velocity.dot(wall_normal);
+
+            if into_wall < 0.0 {
+                target_velocity -= wall_normal * into_wall;
+            }
+        }
+
+        let rate = if target_velocity == Vec3::ZERO {
+            settings.deceleration
+        }else {
+            let alignment = current_velocity.normalize_or_zero().dot(target_velocity.normalize_or_zero());
+            let turn_factor = if current_velocity == Vec3::ZERO {0.0} else {(1.0 - alignment) / 2.0};
+
+            settings.acceleration + (settings.turn_acceleration - settings.acceleration) * turn_factor
+        };
+        let velocity = move_towards(current_velocity, target_velocity, rate * control * delta);
+
+        linear_velocity.x = velocity.x + ground_velocity.x;
+        linear_velocity.z = velocity.z + ground_velocity.z;
+
+        if character_controller.grounded && ground_normal.y > f32::EPSILON && !current_states.0.contains_key(&States::Jumping) {
+            let slope_velocity = -(ground_normal.x * velocity.x + ground_normal.z * velocity.z) / ground_normal.y;
+
+            if slope_velocity.abs() > f32::EPSILON {
+                linear_velocity.y = slope_velocity + ground_velocity.y;
+            }
         }
+    }
+}
+
+pub fn separate_combatants(
+    mut character_query: Query<(Entity, &CharacterControllerSettings, &Collider, &mut Position), (With<CharacterController>, With<InteractNetworkAble>)>,
+    combatant_query: Query<(Entity, &Collider, &Position), (With<CombatantMarker>, Without<CharacterControllerSettings>)>,
+    time_fixed: Res<Time<Fixed>>,
+){
+    let delta = time_fixed.delta().as_secs_f32();
+    let bodies: Vec<(Entity, Vec3, Vec3)> = character_query.iter()
+        .map(|(entity, _, collider, position)| (entity, position.0, collider_half_extents(collider)))
+        .chain(combatant_query.iter().map(|(entity, collider, position)| (entity, position.0, collider_half_extents(collider))))
+        .collect();
+
+    for (entity, settings, collider, mut position) in character_query.iter_mut(){
+        let half_extents = collider_half_extents(collider);
+        let radius = half_extents.x.min(half_extents.z);
+        let mut correction = Vec3::ZERO;
+
+        for (other_entity, other_position, other_half_extents) in bodies.iter() {
+            if *other_entity == entity {
+                continue;
+            }
+
+            let offset = position.0 - *other_position;
 
-        if let Some(StatesValues::Walking(walking_direction)) = current_states.0.get_mut(&States::Walking).unwrap().values{
-            linear_velocity.z = walking_direction.z;
+            if offset.y.abs() >= half_extents.y + other_half_extents.y {
+                continue;
+            }
+
+            let horizontal_offset = offset.with_y(0.0);
+            let distance = horizontal_offset.length();
+            let overlap = radius + other_half_extents.x.min(other_half_extents.z) - distance;
+
+            if overlap <= 0.0 {
+                continue;
+            }
+
+            let direction = if distance > f32::EPSILON {horizontal_offset / distance} else if entity < *other_entity {Vec3::X} else {Vec3::NEG_X};
+
+            correction += direction * overlap * 0.5;
+        }
+
+        if correction != Vec3::ZERO {
+            position.0 += correction * (settings.separation_strength * delta).min(1.0);
+        }
+    }
+}
+
+pub fn character_stamina(
+    mut character_query: Query<(&CharacterControllerSettings, &mut CurrentStates, &mut Stamina), (With<CharacterController>, With<InteractNetworkAble>)>,
+    time_fixed: Res<Time<Fixed>>,
+){
+    let delta = time_fixed.delta().as_secs_f32();
+
+    for (settings, mut current_states, mut stamina) in character_query.iter_mut(){
+        if current_states.0.contains_key(&States::Sprinting) {
+            stamina.current = (stamina.current - settings.stamina_drain * delta).max(0.0);
+
+            if stamina.current <= 0.0 {
+                current_states.stop(&States::Sprinting);
+            }
+        }else if stamina.current < stamina.max {
+            stamina.current = (stamina.current + settings.stamina_regen * delta).min(stamina.max);
         }
     }
 }
 
 pub fn control_gravity(
-    mut character_query: Query<(&mut CurrentStates, &mut LinearVelocity), (With<CharacterController>, With<InteractNetworkAble>)>,
+    mut character_query: Query<(&CharacterController, &mut LinearVelocity), (With<CharacterController>, With<InteractNetworkAble>)>,
     gravity: Res<Gravity>,
     time_fixed: Res<Time<Fixed>>,
 ){
-    let gravity_force = gravity.0.y * time_fixed.delta().as_secs_f32();
+    let delta = time_fixed.delta().as_secs_f32();
 
-    for (_current_states, mut linear_velocity) in character_query.iter_mut(){
-        if linear_velocity.x != 0.0 {
-            linear_velocity.x = if linear_velocity.x > 0.0 { (linear_velocity.x + gravity_force).max(0.0) } else {(linear_velocity.x - gravity_force).min(0.0)};
+    for (character_controller, mut linear_velocity) in character_query.iter_mut(){
+        if character_controller.shape_hit_data.is_none() {
+            linear_velocity.0 += gravity.0 * delta;
         }
+    }
+}
+
+pub fn character_jump(
+    mut character_query: Query<(&CharacterController, &CharacterControllerSettings, &mut CurrentStates, &mut LinearVelocity), (With<CharacterController>, With<InteractNetworkAble>)>,
+    gravity: Res<Gravity>,
+){
+    for (character_controller, settings, mut current_states, mut linear_velocity) in character_query.iter_mut(){
+        let Some(StatesValues::Jumping(launched)) = current_states.0.get(&States::Jumping).and_then(|state_infos| state_infos.values.clone()) else {continue};
+
+        if !launched {
+            linear_velocity.y = (2.0 * gravity.0.length() * settings.jump_height).sqrt() + character_controller.ground_velocity.y.max(0.0);
+
+            if let Some(state_infos) = current_states.0.get_mut(&States::Jumping) {
+                state_infos.values = Some(StatesValues::Jumping(true));
+            }
+        }else if character_controller.grounded && linear_velocity.y <= 0.0 {
+            current_states.stop(&States::Jumping);
+
+            if !current_states.0.contains_key(&States::Walking) {
+                current_states.transition(&States::Idle,StateInfos{
+                    values: None,
+                    ..default()
+                });
+            }
+        }
+    }
+}
+
+pub fn character_dash(
+    mut character_query: Query<(&mut CurrentStates, &mut LinearVelocity), (With<CharacterController>, With<InteractNetworkAble>)>,
+    dash_settings: Res<DashSettings>,
+    tick_manager: Res<TickManager>,
+    rollback: Option<Res<Rollback>>,
+    time_fixed: Res<Time<Fixed>>,
+){
+    let tick = current_tick(&tick_manager, rollback.as_deref());
+    let dash_speed = dash_settings.distance / (dash_settings.duration_ticks.max(1) as f32 * time_fixed.timestep().as_secs_f32());
+
+    for (mut current_states, mut linear_velocity) in character_query.iter_mut(){
+        let Some(StatesValues::Dashing(dash_direction, start_tick)) = current_states.0.get(&States::Dashing).and_then(|state_infos| state_infos.values.clone()) else {continue};
+
+        if (tick - Tick(start_tick)) >= dash_settings.duration_ticks as i16 {
+            let stopped_states = current_states.stop(&States::Dashing).map(|state_infos| state_infos.stopped_states).unwrap_or_default();
+
+            if stopped_states.contains(&States::Walking) {
+                current_states.transition(&States::Walking,StateInfos{
+                    values: Some(StatesValues::Walking(dash_direction)),
+                    ..default()
+                });
+            }else {
+                current_states.transition(&States::Idle,StateInfos{
+                    values: None,
+                    ..default()
+                });
+            }
+
+            if stopped_states.contains(&States::Sprinting) {
+                current_states.transition(&States::Sprinting,StateInfos{
+                    values: None,
+                    ..default()
+                });
+            }
+
+            continue;
+        }
+
+        linear_velocity.x = dash_direction.x * dash_speed;
+        linear_velocity.z = dash_direction.z * dash_speed;
+    }
+}
+
+pub fn character_stagger(
+    mut character_query: Query<&mut CurrentStates, (With<CharacterController>, With<InteractNetworkAble>)>,
+    combat_settings: Res<CombatSettings>,
+    tick_manager: Res<TickManager>,
+    rollback: Option<Res<Rollback>>,
+){
+    let tick = current_tick(&tick_manager, rollback.as_deref());
+
+    for mut current_states in character_query.iter_mut(){
+        let Some(StatesValues::Staggered(start_tick)) = current_states.0.get(&States::Staggered).and_then(|state_infos| state_infos.values.clone()) else {continue};
 
-        if linear_velocity.z != 0.0 {
-            linear_velocity.z = if linear_velocity.z > 0.0 { (linear_velocity.z + gravity_force).max(0.0) } else {(linear_velocity.z - gravity_force).min(0.0)};
+        if (tick - Tick(start_tick)) >= combat_settings.stagger_ticks as i16 {
+            current_states.stop(&States::Staggered);
+            current_states.transition(&States::Idle,StateInfos{
+                values: None,
+                ..default()
+            });
         }
     }
 }
\ No newline at end of file